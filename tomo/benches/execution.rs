@@ -0,0 +1,75 @@
+//! Vergleicht die Ausführung über `fetch` + `execute` (Holen und Dekodieren bei jeder Instruktion,
+//! wie `tick` ohne Cache) mit `tick`, welches dekodierte Instruktionen aus dem Cache nimmt.
+//! Beide Varianten machen sonst dieselbe Arbeit, damit nur der Cache gemessen wird. Gezählt
+//! wird jeweils der schnellste von mehreren abwechselnden Durchläufen, um Rauschen zu dämpfen.
+//!
+//! Ausführen mit `cargo bench --target <native target>`, z.B. `x86_64-unknown-linux-gnu`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tomo::chip8::processor::Processor;
+
+const INSTRUCTIONS: u32 = 5_000_000;
+const ROUNDS: usize = 5;
+
+// Rechenlastige Schleife mit gelegentlichem Zeichnen und BCD
+const ROM: [u8; 22] = [
+    0xA3, 0x00, // 0x200: LD I, 0x300
+    0x70, 0x01, // 0x202: ADD V0, 1
+    0x81, 0x04, // 0x204: ADD V1, V0
+    0x82, 0x13, // 0x206: XOR V2, V1
+    0x83, 0x26, // 0x208: SHR V3, V2
+    0x84, 0x35, // 0x20A: SUB V4, V3
+    0x54, 0x10, // 0x20C: SE V4, V1
+    0x12, 0x02, // 0x20E: JP 0x202
+    0xF4, 0x33, // 0x210: LD B, V4
+    0xD0, 0x15, // 0x212: DRW V0, V1, 5
+    0x12, 0x02, // 0x214: JP 0x202
+];
+
+fn build_processor() -> Processor {
+    let mut processor = Processor::new();
+    processor.load(&ROM).unwrap();
+    processor
+}
+
+fn measure(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    run();
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<16} {:>8.2} ms ({:.2} ns/instruction)",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        elapsed.as_nanos() as f64 / f64::from(INSTRUCTIONS)
+    );
+}
+
+fn main() {
+    let (mut uncached, mut cached) = (Duration::MAX, Duration::MAX);
+    for _ in 0..ROUNDS {
+        let mut processor = build_processor();
+        uncached = uncached.min(measure(|| {
+            for _ in 0..INSTRUCTIONS {
+                processor.tick_timers();
+                let opcode = processor.fetch();
+                black_box(processor.execute(opcode));
+            }
+        }));
+
+        let mut processor = build_processor();
+        cached = cached.min(measure(|| {
+            for _ in 0..INSTRUCTIONS {
+                black_box(processor.tick());
+            }
+        }));
+    }
+    report("fetch + execute", uncached);
+    report("tick (cached)", cached);
+
+    println!("speedup: {:.2}x", uncached.as_secs_f64() / cached.as_secs_f64());
+}
@@ -0,0 +1,249 @@
+//! Erkennt den Befehlssatz einer ROM, die in keiner Datenbank steht
+//!
+//! Ab dem Start des Programms werden alle erreichbaren Instruktionen verfolgt (Sprünge, Aufrufe
+//! und beide Wege von Skips). Jede Instruktion verlangt einen Befehlssatz; gewählt wird der
+//! umfassendste, der vorkommt. Die Sicherheit ist der Anteil der erreichbaren Instruktionen, die
+//! der gewählte Befehlssatz ausführen kann. BNNN und Sprünge aus der ROM heraus lassen sich nicht
+//! verfolgen, dahinter liegender Code wird also nicht gesehen.
+
+use alloc::vec::Vec;
+
+use super::machine::{Variant, HIRES_SIGNATURE, HIRES_START};
+use super::quirks::Quirks;
+use super::OPCODE_SIZE;
+use crate::prelude::*;
+
+// Ab dieser Sicherheit übernimmt `Processor::load` das Ergebnis
+pub const MIN_CONFIDENCE: f32 = 0.75;
+
+// Befehlssätze, die eine ROM benötigen kann. Spätere Einträge einer Familie umfassen die früheren
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InstructionSet {
+    Chip8,
+    // Zwei Seiten Bildspeicher (1260 und 0230)
+    Hires,
+    // Farbkarte und zweites Keypad des VIP
+    Chip8x,
+    // SUPER-CHIP: Scrollen, 128x64 Pixel, große Schrift
+    Schip,
+    // XO-CHIP: Bitebenen, Audio-Muster, 16 Bit Adressen
+    XoChip,
+    MegaChip,
+}
+
+impl InstructionSet {
+    // Nächste von `Processor` unterstützte Variante. SCHIP und XO-CHIP laufen als CHIP-8,
+    // ihre Erweiterungen bis auf 00FD bleiben dabei ungültig
+    pub fn variant(self) -> Variant {
+        match self {
+            InstructionSet::Hires => Variant::Hires,
+            InstructionSet::Chip8x => Variant::Chip8x,
+            InstructionSet::MegaChip => Variant::MegaChip,
+            _ => Variant::Chip8,
+        }
+    }
+
+    // Quirks der Interpreter, für die solche ROMs geschrieben werden, falls sie von denen des
+    // COSMAC VIP abweichen
+    pub fn quirks(self) -> Option<Quirks> {
+        match self {
+            InstructionSet::Schip | InstructionSet::MegaChip => Some(Quirks::superchip()),
+            InstructionSet::XoChip => Some(Quirks { wrap: true, ..Quirks::modern() }),
+            _ => None,
+        }
+    }
+}
+
+// Ergebnis von `RomAnalysis::analyze`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RomAnalysis {
+    pub instruction_set: InstructionSet,
+    // Anteil (0 bis 1) der erreichbaren Instruktionen, die `instruction_set` ausführen kann
+    pub confidence: f32,
+    // Ob 0NNN Unterroutinen in Maschinensprache des CDP1802 aufruft
+    pub machine_code: bool,
+    // Anzahl erreichbarer Instruktionen
+    pub reachable: usize,
+    // Instruktionen, die kein Befehlssatz kennt
+    pub unknown: usize,
+    // Sprünge, deren Ziel erst zur Laufzeit feststeht (BNNN)
+    pub dynamic_jumps: usize,
+}
+
+// Was eine einzelne Instruktion verlangt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Requirement {
+    Set(InstructionSet),
+    MachineCode,
+    Unknown,
+}
+
+#[wasm_bindgen]
+impl RomAnalysis {
+    // Analysiert eine ROM, die ab `load_address` im Speicher liegt
+    pub fn analyze(data: &[u8], load_address: u16) -> RomAnalysis {
+        let hires = load_address == 0x200 && data.starts_with(&HIRES_SIGNATURE);
+        let entry = if hires { HIRES_START } else { load_address };
+        let mut walker = Walker {
+            data,
+            load_address: usize::from(load_address),
+            hires,
+            megachip: false,
+            chip8x: false,
+            dynamic_jumps: 0,
+            visited: alloc::vec![false; data.len()],
+        };
+        let requirements = walker.walk(usize::from(entry));
+
+        let mut instruction_set = if hires { InstructionSet::Hires } else { InstructionSet::Chip8 };
+        let mut machine_code = false;
+        let mut unknown = 0;
+        for requirement in &requirements {
+            match requirement {
+                Requirement::Set(set) => instruction_set = instruction_set.max(*set),
+                Requirement::MachineCode => machine_code = true,
+                Requirement::Unknown => unknown += 1,
+            }
+        }
+        let supported = requirements.iter().filter(|requirement| supports(instruction_set, **requirement)).count();
+        RomAnalysis {
+            instruction_set,
+            confidence: if requirements.is_empty() { 0.0 } else { supported as f32 / requirements.len() as f32 },
+            machine_code,
+            reachable: requirements.len(),
+            unknown,
+            dynamic_jumps: walker.dynamic_jumps,
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.instruction_set.variant()
+    }
+}
+
+// Ob `set` eine Instruktion ausführen kann. Maschinensprache läuft nur auf dem VIP, also mit
+// den Befehlssätzen, die auf dessen Interpreter aufbauen
+fn supports(set: InstructionSet, requirement: Requirement) -> bool {
+    use InstructionSet::*;
+    match requirement {
+        Requirement::Set(Chip8) => true,
+        Requirement::Set(Schip) => matches!(set, Schip | XoChip | MegaChip),
+        Requirement::Set(required) => required == set,
+        Requirement::MachineCode => matches!(set, Chip8 | Hires | Chip8x),
+        Requirement::Unknown => false,
+    }
+}
+
+// Verfolgt den Programmfluss durch die ROM
+struct Walker<'a> {
+    data: &'a [u8],
+    load_address: usize,
+    hires: bool,
+    // Wurde 0011 gesehen, sind 01NN bis 09NN Befehle von MegaChip statt Maschinensprache
+    megachip: bool,
+    // Wurde eine Instruktion von CHIP-8X gesehen, färbt BXYN statt zu springen
+    chip8x: bool,
+    dynamic_jumps: usize,
+    visited: Vec<bool>,
+}
+
+impl Walker<'_> {
+    fn opcode(&self, address: usize) -> Option<u16> {
+        let offset = address.checked_sub(self.load_address)?;
+        let bytes = self.data.get(offset..offset + OPCODE_SIZE)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    // Gibt die Anforderungen aller erreichbaren Instruktionen zurück
+    fn walk(&mut self, entry: usize) -> Vec<Requirement> {
+        let mut requirements = Vec::new();
+        let mut pending = alloc::vec![entry];
+        while let Some(address) = pending.pop() {
+            let opcode = match self.opcode(address) {
+                Some(opcode) if !self.visited[address - self.load_address] => opcode,
+                _ => continue,
+            };
+            self.visited[address - self.load_address] = true;
+            if opcode == 0x0011 {
+                self.megachip = true;
+            }
+            let requirement = self.requirement(opcode);
+            self.chip8x |= requirement == Requirement::Set(InstructionSet::Chip8x);
+            requirements.push(requirement);
+
+            let next = address + OPCODE_SIZE;
+            // Instruktionen mit einem zweiten Wort (F000 NNNN bzw. LDHI 01NN NNNN)
+            let long = opcode == 0xF000 || (self.megachip && opcode & 0xFF00 == 0x0100);
+            let after = if long { next + OPCODE_SIZE } else { next };
+            let nnn = usize::from(opcode & 0x0FFF);
+            match opcode >> 12 {
+                0x0 if opcode == 0x00EE || opcode == 0x00FD => {}
+                0x1 if nnn == address => {}
+                0x1 => pending.push(nnn),
+                0x2 => pending.extend([after, nnn]),
+                0xB if self.chip8x => pending.push(next),
+                // Das Ziel hängt von V0 ab
+                0xB => self.dynamic_jumps += 1,
+                0x3 | 0x4 | 0x9 => pending.extend(self.skip(next)),
+                0x5 if opcode & 0x000F == 0 => pending.extend(self.skip(next)),
+                0xE if matches!(opcode & 0x00FF, 0x9E | 0xA1 | 0xF2 | 0xF5) => pending.extend(self.skip(next)),
+                _ => pending.push(after),
+            }
+        }
+        requirements
+    }
+
+    // Ziele eines Skips: Die nächste Instruktion und die danach (bei XO-CHIP auch über F000 NNNN)
+    fn skip(&self, next: usize) -> [usize; 2] {
+        let width = if self.opcode(next) == Some(0xF000) { 2 * OPCODE_SIZE } else { OPCODE_SIZE };
+        [next, next + width]
+    }
+
+    fn requirement(&self, opcode: u16) -> Requirement {
+        use InstructionSet::*;
+        let (x, n, kk) = (opcode >> 8 & 0x0F, opcode & 0x000F, opcode & 0x00FF);
+        let set = match opcode >> 12 {
+            0x0 => match opcode {
+                0x00E0 | 0x00EE => Chip8,
+                0x00FB..=0x00FF => Schip,
+                _ if opcode & 0xFFF0 == 0x00C0 => Schip,
+                _ if opcode & 0xFFF0 == 0x00D0 => XoChip,
+                0x0010 | 0x0011 => MegaChip,
+                _ if self.megachip && (opcode & 0xFFF0 == 0x00B0 || (0x0100..0x0A00).contains(&opcode)) => MegaChip,
+                0x02A0 => Chip8x,
+                0x0230 if self.hires => Hires,
+                _ => return Requirement::MachineCode,
+            },
+            0x5 => match n {
+                0x0 => Chip8,
+                0x1 => Chip8x,
+                0x2 | 0x3 => XoChip,
+                _ => return Requirement::Unknown,
+            },
+            0x8 => match n {
+                0x0..=0x7 | 0xE => Chip8,
+                _ => return Requirement::Unknown,
+            },
+            0x9 if n != 0 => return Requirement::Unknown,
+            0xD if n == 0 => Schip,
+            0xE => match kk {
+                0x9E | 0xA1 => Chip8,
+                0xF2 | 0xF5 => Chip8x,
+                _ => return Requirement::Unknown,
+            },
+            0xF => match kk {
+                0x07 | 0x0A | 0x15 | 0x18 | 0x1E | 0x29 | 0x33 | 0x55 | 0x65 => Chip8,
+                0x30 | 0x75 | 0x85 => Schip,
+                0x00 if x == 0 => XoChip,
+                0x01 | 0x3A => XoChip,
+                0x02 if x == 0 => XoChip,
+                0xF8 | 0xFB => Chip8x,
+                _ => return Requirement::Unknown,
+            },
+            _ => Chip8,
+        };
+        Requirement::Set(set)
+    }
+}
@@ -0,0 +1,287 @@
+//! RCA CDP1802, die CPU des COSMAC VIP, für Unterroutinen in Maschinensprache (0NNN)
+//!
+//! Der Interpreter des VIP ruft solche Routinen mit P = 3 auf und erwartet die Rückkehr über
+//! `SEP R4` (0xD4). Dabei gelten seine Registerkonventionen: R2 zeigt auf den Stack (X = 2),
+//! R5 ist der PC des CHIP-8 Programms, R6 / R7 zeigen auf VX / VY, RA ist das Index-Register
+//! und RB.1 die Seite des Displays. Unterbrechungen, DMA und Ein-/Ausgabegeräte gibt es nicht:
+//! IDL läuft einfach weiter, EF1-EF4 sind nie gesetzt und INP liefert 0.
+
+use core::ops::Range;
+
+// Register, in das eine Routine mit `SEP` zurückkehrt
+pub const RETURN_REGISTER: u8 = 4;
+
+// Ergebnis von `Cdp1802::call`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallResult {
+    // Ausgeführte Instruktionen
+    pub cycles: u32,
+    // Ob die Routine zurückgekehrt ist, bevor das Limit erreicht wurde
+    pub returned: bool,
+    // Beschriebener Speicherbereich, falls etwas geschrieben wurde
+    pub written: Option<Range<usize>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cdp1802 {
+    // Register R0 bis RF
+    pub r: [u16; 16],
+    // Akkumulator
+    pub d: u8,
+    // Übertrag (Data Flag)
+    pub df: bool,
+    // Register, das als Programmzähler dient
+    pub p: u8,
+    // Register, das als Datenzeiger dient
+    pub x: u8,
+    // Gesicherte X und P (MARK, SAV)
+    pub t: u8,
+    // Unterbrechungen erlaubt
+    pub ie: bool,
+    // Ausgang Q, steuert beim VIP den Lautsprecher
+    pub q: bool,
+}
+
+// Speicherzugriffe, merkt sich den beschriebenen Bereich. Adressen werden auf die Größe
+// des RAMs umgebrochen, wie bei einem nicht vollständig dekodierten Adressbus
+struct Bus<'a> {
+    ram: &'a mut [u8],
+    written: Option<Range<usize>>,
+}
+
+impl Bus<'_> {
+    fn read(&self, address: u16) -> u8 {
+        self.ram[usize::from(address) % self.ram.len()]
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        let address = usize::from(address) % self.ram.len();
+        self.ram[address] = value;
+        self.written = Some(match self.written.take() {
+            Some(range) => range.start.min(address)..range.end.max(address + 1),
+            None => address..address + 1,
+        });
+    }
+}
+
+impl Cdp1802 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Führt ab R(P) aus, bis ein `SEP` auf `RETURN_REGISTER` wechselt oder `limit` Instruktionen
+    // ausgeführt wurden
+    pub fn call(&mut self, ram: &mut [u8], limit: u32) -> CallResult {
+        let mut bus = Bus { ram, written: None };
+        let mut cycles = 0;
+        while cycles < limit && self.p != RETURN_REGISTER {
+            self.step(&mut bus);
+            cycles += 1;
+        }
+        CallResult {
+            cycles,
+            returned: self.p == RETURN_REGISTER,
+            written: bus.written,
+        }
+    }
+
+    fn fetch(&mut self, bus: &Bus) -> u8 {
+        let p = usize::from(self.p);
+        let value = bus.read(self.r[p]);
+        self.r[p] = self.r[p].wrapping_add(1);
+        value
+    }
+
+    fn rx(&self) -> u16 {
+        self.r[usize::from(self.x)]
+    }
+
+    // Addition mit Übertrag, DF ist danach der Übertrag
+    fn add(&mut self, a: u8, b: u8, carry: bool) {
+        let sum = u16::from(a) + u16::from(b) + u16::from(carry);
+        self.d = sum as u8;
+        self.df = sum > 0xFF;
+    }
+
+    // Subtraktion `a - b`, DF ist danach 1, wenn kein Borgen nötig war
+    fn subtract(&mut self, a: u8, b: u8, borrow: bool) {
+        self.add(a, !b, !borrow);
+    }
+
+    // Bedingung der Sprünge x1 bis x7, die Sprünge x9 bis xF sind jeweils ihr Gegenteil
+    fn condition(&self, code: u8) -> bool {
+        let condition = match code & 0x07 {
+            0x00 => true,
+            0x01 => self.q,
+            0x02 => self.d == 0,
+            0x03 => self.df,
+            // EF1 - EF4
+            _ => false,
+        };
+        condition != (code & 0x08 != 0)
+    }
+
+    fn short_branch(&mut self, bus: &Bus, condition: bool) {
+        let p = usize::from(self.p);
+        if condition {
+            let low = bus.read(self.r[p]);
+            self.r[p] = self.r[p] & 0xFF00 | u16::from(low);
+        } else {
+            self.r[p] = self.r[p].wrapping_add(1);
+        }
+    }
+
+    fn long_branch(&mut self, bus: &Bus, condition: bool) {
+        let p = usize::from(self.p);
+        if condition {
+            let address = self.r[p];
+            self.r[p] = u16::from(bus.read(address)) << 8 | u16::from(bus.read(address.wrapping_add(1)));
+        } else {
+            self.r[p] = self.r[p].wrapping_add(2);
+        }
+    }
+
+    fn long_skip(&mut self, condition: bool) {
+        if condition {
+            let p = usize::from(self.p);
+            self.r[p] = self.r[p].wrapping_add(2);
+        }
+    }
+
+    fn step(&mut self, bus: &mut Bus) {
+        let opcode = self.fetch(bus);
+        let n = usize::from(opcode & 0x0F);
+        let x = usize::from(self.x);
+        match opcode >> 4 {
+            // IDL wartet auf eine Unterbrechung, die es hier nicht gibt
+            0x0 if n == 0 => {}
+            // LDN
+            0x0 => self.d = bus.read(self.r[n]),
+            // INC, DEC
+            0x1 => self.r[n] = self.r[n].wrapping_add(1),
+            0x2 => self.r[n] = self.r[n].wrapping_sub(1),
+            // Kurze Sprünge, 38 (SKP) überspringt ein Byte
+            0x3 if n == 0x8 => self.short_branch(bus, false),
+            0x3 => self.short_branch(bus, self.condition(opcode)),
+            // LDA, STR
+            0x4 => {
+                self.d = bus.read(self.r[n]);
+                self.r[n] = self.r[n].wrapping_add(1);
+            }
+            0x5 => bus.write(self.r[n], self.d),
+            0x6 => match n {
+                // IRX sowie OUT 1-7, ohne Ausgabegerät wird dabei nur RX erhöht
+                0x0..=0x7 => self.r[x] = self.r[x].wrapping_add(1),
+                // Beim CDP1802 nicht belegt
+                0x8 => {}
+                // INP 1-7
+                _ => {
+                    self.d = 0;
+                    bus.write(self.rx(), self.d);
+                }
+            },
+            0x7 => match n {
+                // RET, DIS
+                0x0 | 0x1 => {
+                    let value = bus.read(self.rx());
+                    self.r[x] = self.r[x].wrapping_add(1);
+                    self.x = value >> 4;
+                    self.p = value & 0x0F;
+                    self.ie = n == 0x0;
+                }
+                // LDXA, STXD
+                0x2 => {
+                    self.d = bus.read(self.rx());
+                    self.r[x] = self.r[x].wrapping_add(1);
+                }
+                0x3 => {
+                    bus.write(self.rx(), self.d);
+                    self.r[x] = self.r[x].wrapping_sub(1);
+                }
+                // ADC, SDB, SHRC, SMB
+                0x4 => self.add(bus.read(self.rx()), self.d, self.df),
+                0x5 => self.subtract(bus.read(self.rx()), self.d, !self.df),
+                0x6 => {
+                    let carry = self.df;
+                    self.df = self.d & 0x01 != 0;
+                    self.d = self.d >> 1 | u8::from(carry) << 7;
+                }
+                0x7 => self.subtract(self.d, bus.read(self.rx()), !self.df),
+                // SAV, MARK
+                0x8 => bus.write(self.rx(), self.t),
+                0x9 => {
+                    self.t = self.x << 4 | self.p;
+                    bus.write(self.r[2], self.t);
+                    self.x = self.p;
+                    self.r[2] = self.r[2].wrapping_sub(1);
+                }
+                // REQ, SEQ
+                0xA => self.q = false,
+                0xB => self.q = true,
+                // ADCI, SDBI, SHLC, SMBI
+                0xC => {
+                    let value = self.fetch(bus);
+                    self.add(value, self.d, self.df);
+                }
+                0xD => {
+                    let value = self.fetch(bus);
+                    self.subtract(value, self.d, !self.df);
+                }
+                0xE => {
+                    let carry = self.df;
+                    self.df = self.d & 0x80 != 0;
+                    self.d = self.d << 1 | u8::from(carry);
+                }
+                _ => {
+                    let value = self.fetch(bus);
+                    self.subtract(self.d, value, !self.df);
+                }
+            },
+            // GLO, GHI, PLO, PHI
+            0x8 => self.d = self.r[n] as u8,
+            0x9 => self.d = (self.r[n] >> 8) as u8,
+            0xA => self.r[n] = self.r[n] & 0xFF00 | u16::from(self.d),
+            0xB => self.r[n] = self.r[n] & 0x00FF | u16::from(self.d) << 8,
+            0xC => match n {
+                // NOP
+                0x4 => {}
+                // LSKP
+                0x8 => self.long_skip(true),
+                // LSIE
+                0xC => self.long_skip(self.ie),
+                // LSNQ, LSNZ, LSNF bzw. LSQ, LSZ, LSDF
+                0x5..=0x7 | 0xD..=0xF => self.long_skip(self.condition(opcode ^ 0x0C)),
+                // Lange Sprünge
+                _ => self.long_branch(bus, self.condition(opcode)),
+            },
+            // SEP, SEX
+            0xD => self.p = n as u8,
+            0xE => self.x = n as u8,
+            // SHR, SHL
+            0xF if n == 0x6 => {
+                self.df = self.d & 0x01 != 0;
+                self.d >>= 1;
+            }
+            0xF if n == 0xE => {
+                self.df = self.d & 0x80 != 0;
+                self.d <<= 1;
+            }
+            _ => {
+                // Arithmetik und Logik mit M(RX) bzw. mit dem nächsten Byte (F8 - FF)
+                let value = if n & 0x08 != 0 { self.fetch(bus) } else { bus.read(self.rx()) };
+                match n & 0x07 {
+                    // LDX / LDI
+                    0x0 => self.d = value,
+                    0x1 => self.d |= value,
+                    0x2 => self.d &= value,
+                    0x3 => self.d ^= value,
+                    0x4 => self.add(value, self.d, false),
+                    // SD: M - D
+                    0x5 => self.subtract(value, self.d, false),
+                    // SM: D - M
+                    _ => self.subtract(self.d, value, false),
+                }
+            }
+        }
+    }
+}
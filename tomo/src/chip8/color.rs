@@ -0,0 +1,110 @@
+//! Farbschicht von CHIP-8X (COSMAC VIP mit Farbkarte VP-590)
+//!
+//! Das Display bleibt einfarbig, die Farbkarte legt aber für Blöcke von 8x1 Pixeln fest, in
+//! welcher Farbe aktive Pixel erscheinen. Inaktive Pixel zeigen die Hintergrundfarbe, die
+//! mit 02A0 durchgeschaltet wird (Blau, Schwarz, Grün, Rot).
+
+use super::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use crate::prelude::*;
+
+// Breite eines Farbblocks in Pixeln
+pub const COLOR_BLOCK_WIDTH: usize = 8;
+const COLUMNS: usize = DISPLAY_WIDTH / COLOR_BLOCK_WIDTH;
+// Höhe eines groben Blocks (BXY0) in Pixeln
+const COARSE_HEIGHT: usize = 4;
+
+// Vordergrundfarben der VP-590 (0xRRGGBB), der Index ist der Wert aus VY
+pub const FOREGROUND_COLORS: [u32; 8] = [
+    0x000000, // Schwarz
+    0xFF0000, // Rot
+    0x0000FF, // Blau
+    0xFF00FF, // Violett
+    0x00FF00, // Grün
+    0xFFFF00, // Gelb
+    0x00FFFF, // Türkis
+    0xFFFFFF, // Weiß
+];
+
+// Hintergrundfarben in der Reihenfolge von 02A0
+pub const BACKGROUND_COLORS: [u32; 4] = [0x000080, 0x000000, 0x008000, 0x800000];
+
+// Nach dem Einschalten sind alle Blöcke rot
+const DEFAULT_FOREGROUND: u8 = 1;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorLayer {
+    // Index in `BACKGROUND_COLORS`
+    background: u8,
+    // Index in `FOREGROUND_COLORS` je Block aus 8x1 Pixeln, zeilenweise
+    blocks: [u8; COLUMNS * DISPLAY_HEIGHT],
+}
+
+#[wasm_bindgen]
+impl ColorLayer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        ColorLayer {
+            background: 0,
+            blocks: [DEFAULT_FOREGROUND; COLUMNS * DISPLAY_HEIGHT],
+        }
+    }
+
+    pub fn background(&self) -> u32 {
+        BACKGROUND_COLORS[usize::from(self.background)]
+    }
+
+    // Farbe aktiver Pixel an der Position
+    pub fn foreground(&self, y: usize, x: usize) -> u32 {
+        FOREGROUND_COLORS[usize::from(self.blocks[y * COLUMNS + x / COLOR_BLOCK_WIDTH])]
+    }
+
+    // Farbe eines Pixels je nachdem, ob es aktiv ist
+    pub fn color(&self, y: usize, x: usize, active: bool) -> u32 {
+        if active {
+            self.foreground(y, x)
+        } else {
+            self.background()
+        }
+    }
+
+    // 02A0: Nächste Hintergrundfarbe
+    pub fn next_background(&mut self) {
+        self.background = (self.background + 1) % BACKGROUND_COLORS.len() as u8;
+    }
+
+    // BXY0: Färbt grobe Blöcke (8x4 Pixel). Die unteren 4 Bit von `horizontal` bzw. `vertical`
+    // sind der erste Block, die oberen 4 Bit die Anzahl weiterer Blöcke
+    pub fn fill_zones(&mut self, horizontal: u8, vertical: u8, color: u8) {
+        let (x, width) = (usize::from(horizontal & 0x0F), usize::from(horizontal >> 4) + 1);
+        let (y, height) = (usize::from(vertical & 0x0F), usize::from(vertical >> 4) + 1);
+        let rows = DISPLAY_HEIGHT / COARSE_HEIGHT;
+        for zone_y in y..(y + height).min(rows) {
+            for row in zone_y * COARSE_HEIGHT..(zone_y + 1) * COARSE_HEIGHT {
+                self.fill_row(row, x, width, color);
+            }
+        }
+    }
+
+    // BXYN: Färbt `rows` Zeilen des Blocks (8x1 Pixel) an der Pixelposition `x`, `y`
+    pub fn fill_rows(&mut self, x: u8, y: u8, rows: u8, color: u8) {
+        let start = usize::from(y) % DISPLAY_HEIGHT;
+        for row in start..(start + usize::from(rows)).min(DISPLAY_HEIGHT) {
+            self.fill_row(row, usize::from(x) % DISPLAY_WIDTH / COLOR_BLOCK_WIDTH, 1, color);
+        }
+    }
+}
+
+impl ColorLayer {
+    fn fill_row(&mut self, row: usize, column: usize, count: usize, color: u8) {
+        for column in column..(column + count).min(COLUMNS) {
+            self.blocks[row * COLUMNS + column] = color & 0x07;
+        }
+    }
+}
+
+impl Default for ColorLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,159 @@
+//! Steuerung der Ausführung: Pause, Geschwindigkeit und Einzelbilder
+//!
+//! Der `Controller` wird einmal pro Bild des Hosts (60 Hz) mit dem `Processor` aufgerufen
+//! und entscheidet, wie viel emulierte Zeit vergeht. Die Geschwindigkeit wirkt auf
+//! Instruktionen und Timer gleichermaßen: Bei 0.25x laufen pro Bild ein Viertel der
+//! Instruktionen und die Timer zählen nur jedes vierte Bild herunter.
+
+use crate::chip8::processor::{FrameResult, Processor, RunResult, StopReason};
+use crate::prelude::*;
+
+pub const MIN_SPEED: f32 = 0.25;
+pub const MAX_SPEED: f32 = 16.0;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Controller {
+    // Faktor für die Geschwindigkeit, zwischen `MIN_SPEED` und `MAX_SPEED`
+    speed: f32,
+    // Läuft mit `MAX_SPEED`, ohne `speed` zu verändern
+    pub turbo: bool,
+    paused: bool,
+    // Noch nicht ausgeführte Bruchteile von Instruktionen
+    pending: f32,
+    // Bereits ausgeführte Instruktionen des aktuellen emulierten Frames
+    frame_cycles: u32,
+}
+
+#[wasm_bindgen]
+impl Controller {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Controller {
+            speed: 1.0,
+            turbo: false,
+            paused: false,
+            pending: 0.0,
+            frame_cycles: 0,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = if speed.is_nan() { 1.0 } else { speed.clamp(MIN_SPEED, MAX_SPEED) };
+    }
+
+    // Verdoppelt bzw. halbiert die Geschwindigkeit
+    pub fn faster(&mut self) {
+        self.set_speed(self.speed * 2.0);
+    }
+
+    pub fn slower(&mut self) {
+        self.set_speed(self.speed / 2.0);
+    }
+
+    // Tatsächliche Geschwindigkeit unter Berücksichtigung von `turbo`
+    pub fn effective_speed(&self) -> f32 {
+        if self.turbo {
+            MAX_SPEED
+        } else {
+            self.speed
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    // Ein Bild des Hosts: Führt `effective_speed` emulierte Frames aus. Liefert `None`,
+    // wenn pausiert ist oder bei langsamer Geschwindigkeit noch keine Instruktion fällig ist
+    pub fn frame(&mut self, processor: &mut Processor) -> Option<FrameResult> {
+        if self.paused {
+            return None;
+        }
+        self.pending += self.effective_speed() * f32::from(processor.tick_rate.max(1));
+        let cycles = self.pending as u32;
+        self.pending -= cycles as f32;
+        if cycles == 0 {
+            return None;
+        }
+        Some(self.execute(processor, cycles))
+    }
+
+    // Pausiert und führt einen emulierten Frame aus (bzw. den Rest des angefangenen Frames)
+    pub fn advance(&mut self, processor: &mut Processor) -> FrameResult {
+        self.paused = true;
+        let tick_rate = u32::from(processor.tick_rate.max(1));
+        let cycles = tick_rate.saturating_sub(self.frame_cycles);
+        self.execute(processor, if cycles == 0 { tick_rate } else { cycles })
+    }
+
+    // Vergisst angefangene Frames, z.B. nach dem Laden einer neuen ROM
+    pub fn reset(&mut self) {
+        self.pending = 0.0;
+        self.frame_cycles = 0;
+    }
+}
+
+impl Controller {
+    // Führt `cycles` Instruktionen aus und zählt die Timer zu Beginn jedes emulierten Frames herunter
+    fn execute(&mut self, processor: &mut Processor, mut cycles: u32) -> FrameResult {
+        let tick_rate = u32::from(processor.tick_rate.max(1));
+        let mut total = RunResult {
+            cycles: 0,
+            stop: StopReason::Cycles,
+            opcode: 0,
+            display_changed: false,
+            sound_active: false,
+            waiting_for_key: false,
+            halt: None,
+            idle_cycles: 0,
+        };
+        processor.begin_frame();
+        // Die Tick-Rate kann sich seit dem letzten Aufruf geändert haben
+        if self.frame_cycles >= tick_rate {
+            self.frame_cycles = 0;
+        }
+        while cycles > 0 {
+            if self.frame_cycles == 0 {
+                processor.tick_timers();
+            }
+            // Wartet der CPU, vergeht die Zeit trotzdem
+            let chunk = cycles.min(tick_rate - self.frame_cycles);
+            let result = processor.run(chunk);
+            total = RunResult {
+                cycles: total.cycles + result.cycles,
+                idle_cycles: total.idle_cycles + result.idle_cycles,
+                display_changed: total.display_changed || result.display_changed,
+                ..result
+            };
+            if matches!(result.stop, StopReason::InvalidOpcode | StopReason::Halted) {
+                break;
+            }
+            cycles -= chunk;
+            self.frame_cycles = (self.frame_cycles + chunk) % tick_rate;
+        }
+        processor.end_frame(total)
+    }
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
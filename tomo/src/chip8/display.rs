@@ -1,22 +1,88 @@
-use std::fmt;
+use core::fmt;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 use crate::prelude::*;
 
-use super::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use super::{DISPLAY_HEIGHT, DISPLAY_WIDTH, MAX_DISPLAY_HEIGHT};
 
+// Farben für die Darstellung (jeweils 0xRRGGBB)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Colors {
+    // Inaktive Pixel
+    pub background: u32,
+    // Aktive Pixel
+    pub foreground: u32,
+    // Rahmen, während der Piep-Ton läuft
+    pub buzzer: u32,
+    // Rahmen, während kein Ton läuft
+    pub silence: u32,
+}
+
+#[wasm_bindgen]
+impl Colors {
+    #[wasm_bindgen(constructor)]
+    pub fn new(background: u32, foreground: u32) -> Self {
+        Colors {
+            background,
+            foreground,
+            ..Self::default()
+        }
+    }
+}
+
+impl Colors {
+    // Wandelt "#RRGGBB" in 0xRRGGBB um
+    pub fn parse_hex(color: &str) -> Option<u32> {
+        let hex = color.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    // Wandelt 0xRRGGBB in "#RRGGBB" um
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(color: u32) -> String {
+        f!("#{:06X}", color & 0xFFFFFF)
+    }
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Colors {
+            background: 0x000000,
+            foreground: 0xFFFFFF,
+            buzzer: 0xFFAA00,
+            silence: 0x000000,
+        }
+    }
+}
+
+// Eine Zeile muss genau in ein `u64` passen
+const _: () = assert!(DISPLAY_WIDTH == 64);
+
+// Bildspeicher mit einem Bit pro Pixel: Jede Zeile ist ein `u64`, dessen
+// höchstwertiges Bit das Pixel ganz links (x = 0) ist. Es ist immer Platz für
+// `MAX_DISPLAY_HEIGHT` Zeilen, sichtbar sind aber nur die ersten `height`
 #[wasm_bindgen]
 #[derive(Copy, Clone)]
 pub struct Display {
-    vram: [[u8; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+    rows: [u64; MAX_DISPLAY_HEIGHT],
+    // Als `u8`, damit die Höhe zusammen mit `changed` in 8 Bytes passt
+    height: u8,
+    // Ob sich seit dem letzten `take_changed` ein Pixel verändert hat
+    changed: bool,
 }
 
 
 impl fmt::Display for Display {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (i, row) in self.vram.iter().enumerate() {
-            for (j, _) in row.iter().enumerate() {
-                write!(f, "{}", if self.get_pixel(i, j) { "⬜" } else { "□" }).expect("Could not read vram");
+        for y in 0..self.height() {
+            for x in 0..DISPLAY_WIDTH {
+                write!(f, "{}", if self.get_pixel(y, x) { "⬜" } else { "□" }).expect("Could not read vram");
             }
-            write!(f, "\n").expect("Could not write");
+            writeln!(f).expect("Could not write");
         }
 
         Ok(())
@@ -27,25 +93,84 @@ impl fmt::Display for Display {
 #[wasm_bindgen]
 impl Display {
     pub fn new() -> Self {
+        Self::with_height(DISPLAY_HEIGHT)
+    }
+
+    // Display mit eigener Höhe (1 bis `MAX_DISPLAY_HEIGHT` Zeilen), z.B. 64 für Hires CHIP-8
+    pub fn with_height(height: usize) -> Self {
         Display {
-            vram: [[0; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+            rows: [0; MAX_DISPLAY_HEIGHT],
+            height: height.clamp(1, MAX_DISPLAY_HEIGHT) as u8,
+            changed: false,
         }
     }
 
+    pub fn height(&self) -> usize {
+        usize::from(self.height)
+    }
+
+    // Ändert die Höhe und löscht dabei das Display
+    pub fn set_height(&mut self, height: usize) {
+        let height = height.clamp(1, MAX_DISPLAY_HEIGHT) as u8;
+        self.cls();
+        self.changed |= height != self.height;
+        self.height = height;
+    }
+
     pub fn set_pixel(&mut self, y: usize, x: usize, state: bool) {
-        self.vram[y][x] = if state { 1 } else { 0 };
+        let mask = Self::mask(x);
+        let row = if state { self.rows[y] | mask } else { self.rows[y] & !mask };
+        self.changed |= row != self.rows[y];
+        self.rows[y] = row;
     }
 
     pub fn get_pixel(&self, y: usize, x: usize) -> bool {
-        self.vram[y][x] == 1
+        self.rows[y] & Self::mask(x) != 0
     }
 
+    // Ganze Zeile als Bits (x = 0 im höchstwertigen Bit), z.B. für schnelles Rendern
+    pub fn row(&self, y: usize) -> u64 {
+        self.rows[y]
+    }
 
     pub fn cls(&mut self) {
-        for x in 0..DISPLAY_WIDTH {
-            for y in 0..DISPLAY_HEIGHT {
-                self.vram[y][x] = 0;
-            }
-        }
+        self.changed |= self.rows.iter().any(|row| *row != 0);
+        self.rows = [0; MAX_DISPLAY_HEIGHT];
+    }
+
+    // Zeichnet eine Zeile eines Sprites (8 Pixel) per XOR ab Spalte `x`. Mit `wrap`
+    // werden Pixel jenseits des rechten Randes links fortgesetzt, ansonsten abgeschnitten.
+    // Gibt zurück, ob dabei ein Pixel gelöscht wurde (Kollision)
+    pub fn draw_row(&mut self, y: usize, x: usize, sprite: u8, wrap: bool) -> bool {
+        let sprite = u64::from(sprite) << (DISPLAY_WIDTH - 8);
+        let bits = if wrap { sprite.rotate_right(x as u32) } else { sprite >> x };
+        let collision = self.rows[y] & bits != 0;
+        self.rows[y] ^= bits;
+        self.changed |= bits != 0;
+        collision
+    }
+
+    // Gibt zurück, ob sich das Display seit dem letzten Aufruf verändert hat
+    pub fn take_changed(&mut self) -> bool {
+        core::mem::replace(&mut self.changed, false)
+    }
+}
+
+impl Display {
+    // Bit eines Pixels innerhalb seiner Zeile
+    fn mask(x: usize) -> u64 {
+        1 << (DISPLAY_WIDTH - 1 - x)
+    }
+
+    // Ersetzt eine ganze Zeile (x = 0 im höchstwertigen Bit)
+    pub(crate) fn set_row(&mut self, y: usize, row: u64) {
+        self.changed |= row != self.rows[y];
+        self.rows[y] = row;
+    }
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,103 @@
+//! Ereignisse des Emulators
+//!
+//! Statt nach jedem Frame den Zustand abzufragen, können Frontends über `EventHandler`
+//! (nativ als Trait-Objekt, in JavaScript als Funktion) auf Ereignisse reagieren.
+//! Die Ereignisse werden direkt während der Ausführung gemeldet.
+
+use crate::chip8::processor::HaltReason;
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    SoundStarted,
+    SoundStopped,
+    // Wird höchstens einmal pro `run` bzw. `run_frame` gemeldet
+    DisplayChanged,
+    // FX0A beginnt bzw. hört auf, auf eine Taste zu warten
+    KeyWaitStarted,
+    KeyWaitEnded,
+    InvalidOpcode(u16),
+    Halted(HaltReason),
+}
+
+// Art eines Ereignisses als Klassen-Repräsentation für JS
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    SoundStarted,
+    SoundStopped,
+    DisplayChanged,
+    KeyWaitStarted,
+    KeyWaitEnded,
+    InvalidOpcode,
+    Halted,
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::SoundStarted => EventKind::SoundStarted,
+            Event::SoundStopped => EventKind::SoundStopped,
+            Event::DisplayChanged => EventKind::DisplayChanged,
+            Event::KeyWaitStarted => EventKind::KeyWaitStarted,
+            Event::KeyWaitEnded => EventKind::KeyWaitEnded,
+            Event::InvalidOpcode(_) => EventKind::InvalidOpcode,
+            Event::Halted(_) => EventKind::Halted,
+        }
+    }
+
+    // Zusätzlicher Wert: Der Opcode bzw. der `HaltReason`, ansonsten 0
+    pub fn value(&self) -> u32 {
+        match self {
+            Event::InvalidOpcode(opcode) => u32::from(*opcode),
+            Event::Halted(reason) => *reason as u32,
+            _ => 0,
+        }
+    }
+}
+
+pub trait EventHandler {
+    fn on_event(&mut self, event: Event);
+}
+
+impl<F: FnMut(Event)> EventHandler for F {
+    fn on_event(&mut self, event: Event) {
+        self(event)
+    }
+}
+
+// Ruft eine JavaScript-Funktion mit `(kind: EventKind, value: number)` auf
+#[cfg(all(target_arch = "wasm32", feature = "alloc"))]
+pub struct JsEventHandler {
+    callback: js_sys::Function,
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "alloc"))]
+impl JsEventHandler {
+    pub fn new(callback: js_sys::Function) -> Self {
+        JsEventHandler { callback }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "alloc"))]
+impl EventHandler for JsEventHandler {
+    fn on_event(&mut self, event: Event) {
+        // Fehler in der Funktion sollen den Emulator nicht anhalten
+        let _ = self.callback.call2(&JsValue::NULL, &JsValue::from(event.kind() as u8), &JsValue::from(event.value()));
+    }
+}
+
+// Bereits gemeldete Ereignisse eines Frames als Bitmaske
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct EventSet(u8);
+
+impl EventSet {
+    pub fn insert(&mut self, kind: EventKind) {
+        self.0 |= 1 << kind as u8;
+    }
+
+    pub fn contains(&self, kind: EventKind) -> bool {
+        self.0 & (1 << kind as u8) != 0
+    }
+}
@@ -0,0 +1,253 @@
+//! Schriftsätze für FX29 (kleine Ziffern, 4x5) und FX30 (große Ziffern, 8x10)
+//!
+//! Die Original-Interpreter brachten eigene Glyphen mit und legten sie an unterschiedlichen
+//! Stellen ab. Beim COSMAC VIP, DREAM 6800 und ETI-660 lag die Schrift im ROM außerhalb der
+//! 4 KB, hier wird sie daher im Bereich des Interpreters ab 0x000 abgelegt. CHIP-48 und
+//! SCHIP legten sie bei 0x50 ab, SCHIP zusätzlich die große Schrift direkt dahinter.
+
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+use core::fmt;
+
+use super::FONT;
+use crate::prelude::*;
+
+// Größe der kleinen Schrift (16 Glyphen zu 5 Bytes)
+pub const SMALL_FONT_SIZE: usize = 80;
+// Größte große Schrift (16 Glyphen zu 10 Bytes), SCHIP kennt nur die Ziffern 0-9
+pub const BIG_FONT_SIZE: usize = 160;
+
+const VIP_FONT: [u8; SMALL_FONT_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x60, 0x20, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0xA0, 0xA0, 0xF0, 0x20, 0x20, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x10, 0x10, 0x10, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xF0, 0x50, 0x70, 0x50, 0xF0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xF0, 0x50, 0x50, 0x50, 0xF0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+const DREAM_6800_FONT: [u8; SMALL_FONT_SIZE] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x40, 0x40, 0x40, 0x40, 0x40, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0x80, 0xA0, 0xA0, 0xE0, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0xC0, 0xA0, 0xE0, 0xA0, 0xC0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+const ETI_660_FONT: [u8; SMALL_FONT_SIZE] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x20, 0x20, 0x20, 0x20, 0x20, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0xA0, 0xA0, 0xE0, 0x20, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0x80, 0x80, 0xE0, 0xA0, 0xE0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0x20, 0x20, 0xE0, 0xA0, 0xE0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+// Große Ziffern von SCHIP 1.1
+const SCHIP_BIG_FONT: [u8; 100] = [
+    0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C, // 0
+    0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C, // 1
+    0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF, // 2
+    0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C, // 3
+    0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C, // 5
+    0x3E, 0x7C, 0xE0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C, // 6
+    0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60, // 7
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C, // 8
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C, // 9
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontError {
+    // Eine eigene Schrift muss 80 (nur klein), 180 (SCHIP) oder 240 Bytes (16 große Glyphen) lang sein
+    Size(usize),
+    // Die Schrift passt an dieser Adresse nicht in den Speicher
+    Address(u16),
+    // Kleine und große Schrift überschneiden sich
+    Overlap,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Size(size) => write!(f, "Invalid font size: {} bytes (expected 80, 180 or 240)", size),
+            FontError::Address(address) => write!(f, "The font does not fit into memory at {:#05X}", address),
+            FontError::Overlap => write!(f, "The small and the big font overlap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FontError {}
+
+#[cfg(feature = "alloc")]
+impl From<FontError> for JsValue {
+    fn from(error: FontError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Eingebaute Schriftsätze
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontSet {
+    // Original-Interpreter des COSMAC VIP
+    Vip,
+    // CHIPOS des DREAM 6800
+    Dream6800,
+    // ETI-660 Learner's Microcomputer
+    Eti660,
+    // CHIP-48 des HP-48, heute die verbreitetste Schrift
+    Chip48,
+    // SCHIP 1.1: Schrift von CHIP-48 und große Ziffern für FX30
+    Schip,
+    // Vom Benutzer geladen
+    Custom,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Font {
+    set: FontSet,
+    // Adresse der kleinen Schrift, FX29 zeigt auf `address + 5 * Vx`
+    pub address: u16,
+    // Adresse der großen Schrift, FX30 zeigt auf `big_address + 10 * Vx`
+    pub big_address: u16,
+    small: [u8; SMALL_FONT_SIZE],
+    big: [u8; BIG_FONT_SIZE],
+    // Länge der großen Schrift in Bytes, 0 wenn FX30 nicht unterstützt wird
+    big_size: usize,
+}
+
+#[wasm_bindgen]
+impl Font {
+    // Bisheriges Verhalten von Tomo: Schrift von CHIP-48 bei 0x000, keine große Schrift
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Font {
+            address: 0,
+            big_address: SMALL_FONT_SIZE as u16,
+            ..Font::from_set(FontSet::Chip48)
+        }
+    }
+
+    // Schrift eines Original-Interpreters an dessen Adresse. `FontSet::Custom` liefert eine leere Schrift
+    pub fn from_set(set: FontSet) -> Self {
+        let (small, address) = match set {
+            FontSet::Vip => (VIP_FONT, 0x000),
+            FontSet::Dream6800 => (DREAM_6800_FONT, 0x000),
+            FontSet::Eti660 => (ETI_660_FONT, 0x000),
+            FontSet::Chip48 | FontSet::Schip => (FONT, 0x050),
+            FontSet::Custom => ([0; SMALL_FONT_SIZE], 0x000),
+        };
+        let mut font = Font {
+            set,
+            address,
+            big_address: address + SMALL_FONT_SIZE as u16,
+            small,
+            big: [0; BIG_FONT_SIZE],
+            big_size: 0,
+        };
+        if set == FontSet::Schip {
+            font.set_big(&SCHIP_BIG_FONT);
+        }
+        font
+    }
+
+    pub fn set(&self) -> FontSet {
+        self.set
+    }
+
+    // Ob FX30 unterstützt wird
+    pub fn has_big(&self) -> bool {
+        self.big_size > 0
+    }
+}
+
+// Fehler lassen sich nur mit Heap als JavaScript-Wert übergeben
+#[cfg_attr(feature = "alloc", wasm_bindgen)]
+impl Font {
+    // Eigene Schrift: 80 Bytes kleine Schrift, optional gefolgt von 100 oder 160 Bytes großer Schrift
+    pub fn custom(data: &[u8]) -> Result<Font, FontError> {
+        match data.len() {
+            80 | 180 | 240 => {}
+            size => return Err(FontError::Size(size)),
+        }
+        let mut font = Font::from_set(FontSet::Custom);
+        font.small.copy_from_slice(&data[..SMALL_FONT_SIZE]);
+        font.set_big(&data[SMALL_FONT_SIZE..]);
+        Ok(font)
+    }
+
+    // Prüft, ob beide Schriften an ihren Adressen in einen RAM der Größe `ram_size` passen
+    pub fn validate(&self, ram_size: usize) -> Result<(), FontError> {
+        let small = usize::from(self.address)..usize::from(self.address) + SMALL_FONT_SIZE;
+        let big = usize::from(self.big_address)..usize::from(self.big_address) + self.big_size;
+        if small.end > ram_size {
+            return Err(FontError::Address(self.address));
+        }
+        if self.has_big() {
+            if big.end > ram_size {
+                return Err(FontError::Address(self.big_address));
+            }
+            if small.start < big.end && big.start < small.end {
+                return Err(FontError::Overlap);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Font {
+    fn set_big(&mut self, data: &[u8]) {
+        self.big[..data.len()].copy_from_slice(data);
+        self.big_size = data.len();
+    }
+
+    // Glyphen der kleinen Schrift
+    pub fn small_glyphs(&self) -> &[u8] {
+        &self.small
+    }
+
+    // Glyphen der großen Schrift, leer wenn FX30 nicht unterstützt wird
+    pub fn big_glyphs(&self) -> &[u8] {
+        &self.big[..self.big_size]
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,184 @@
+// Art einer Instruktion, unabhängig von ihren Operanden (Register, Adressen, ...)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    // 00E0
+    Cls,
+    // 00EE
+    Ret,
+    // 00FD (SCHIP). Mit Maschinensprache außer bei MegaChip ein Aufruf wie 0NNN
+    Exit,
+    // 0NNN, Unterroutine in Maschinensprache des CDP1802
+    Sys,
+    // 1NNN
+    Jp,
+    // 2NNN
+    Call,
+    // 3XKK
+    SeByte,
+    // 4XKK
+    SneByte,
+    // 5XY0
+    SeReg,
+    // 5XY1 (CHIP-8X)
+    AddNibbles,
+    // 6XKK
+    LdByte,
+    // 7XKK
+    AddByte,
+    // 8XY0
+    LdReg,
+    // 8XY1
+    Or,
+    // 8XY2
+    And,
+    // 8XY3
+    Xor,
+    // 8XY4
+    AddReg,
+    // 8XY5
+    Sub,
+    // 8XY6
+    Shr,
+    // 8XY7
+    Subn,
+    // 8XYE
+    Shl,
+    // 9XY0
+    SneReg,
+    // ANNN
+    LdI,
+    // BNNN
+    JpOffset,
+    // CXKK
+    Rnd,
+    // DXYN
+    Drw,
+    // EX9E
+    Skp,
+    // EXA1
+    Sknp,
+    // EXF2 / EXF5 (CHIP-8X, zweites Keypad)
+    SkpSecond,
+    SknpSecond,
+    // FX07
+    LdVxDt,
+    // FX0A
+    LdVxK,
+    // FX15
+    LdDtVx,
+    // FX18
+    LdStVx,
+    // FX1E
+    AddI,
+    // FX29
+    LdF,
+    // FX30 (SCHIP)
+    LdHf,
+    // FX33
+    LdB,
+    // FX55
+    LdIVx,
+    // FX65
+    LdVxI,
+    // FXF8 (CHIP-8X, Tonhöhe)
+    Pitch,
+    // FXFB (CHIP-8X, Eingabe vom I/O-Port)
+    Input,
+    // Nicht unterstützter oder ungültiger Opcode
+    Invalid,
+}
+
+impl Instruction {
+    // Opcode auftrennen in verschiedene Nibbles und die Instruktion bestimmen
+    pub fn decode(opcode: u16) -> Instruction {
+        let nibbles = (
+            (opcode & 0xF000) >> 12,
+            (opcode & 0x0F00) >> 8,
+            (opcode & 0x00F0) >> 4,
+            opcode & 0x000F,
+        );
+
+        match nibbles {
+            (0x00, 0x00, 0x0e, 0x00) => Instruction::Cls,
+            (0x00, 0x00, 0x0e, 0x0e) => Instruction::Ret,
+            (0x00, 0x00, 0x0f, 0x0d) => Instruction::Exit,
+            (0x00, _, _, _) => Instruction::Sys,
+            (0x01, _, _, _) => Instruction::Jp,
+            (0x02, _, _, _) => Instruction::Call,
+            (0x03, _, _, _) => Instruction::SeByte,
+            (0x04, _, _, _) => Instruction::SneByte,
+            (0x05, _, _, 0x00) => Instruction::SeReg,
+            (0x05, _, _, 0x01) => Instruction::AddNibbles,
+            (0x06, _, _, _) => Instruction::LdByte,
+            (0x07, _, _, _) => Instruction::AddByte,
+            (0x08, _, _, 0x00) => Instruction::LdReg,
+            (0x08, _, _, 0x01) => Instruction::Or,
+            (0x08, _, _, 0x02) => Instruction::And,
+            (0x08, _, _, 0x03) => Instruction::Xor,
+            (0x08, _, _, 0x04) => Instruction::AddReg,
+            (0x08, _, _, 0x05) => Instruction::Sub,
+            (0x08, _, _, 0x06) => Instruction::Shr,
+            (0x08, _, _, 0x07) => Instruction::Subn,
+            (0x08, _, _, 0x0e) => Instruction::Shl,
+            (0x09, _, _, 0x00) => Instruction::SneReg,
+            (0x0a, _, _, _) => Instruction::LdI,
+            (0x0b, _, _, _) => Instruction::JpOffset,
+            (0x0c, _, _, _) => Instruction::Rnd,
+            (0x0d, _, _, _) => Instruction::Drw,
+            (0x0e, _, 0x09, 0x0e) => Instruction::Skp,
+            (0x0e, _, 0x0a, 0x01) => Instruction::Sknp,
+            (0x0e, _, 0x0f, 0x02) => Instruction::SkpSecond,
+            (0x0e, _, 0x0f, 0x05) => Instruction::SknpSecond,
+            (0x0f, _, 0x00, 0x07) => Instruction::LdVxDt,
+            (0x0f, _, 0x00, 0x0a) => Instruction::LdVxK,
+            (0x0f, _, 0x01, 0x05) => Instruction::LdDtVx,
+            (0x0f, _, 0x01, 0x08) => Instruction::LdStVx,
+            (0x0f, _, 0x01, 0x0e) => Instruction::AddI,
+            (0x0f, _, 0x02, 0x09) => Instruction::LdF,
+            (0x0f, _, 0x03, 0x00) => Instruction::LdHf,
+            (0x0f, _, 0x03, 0x03) => Instruction::LdB,
+            (0x0f, _, 0x05, 0x05) => Instruction::LdIVx,
+            (0x0f, _, 0x06, 0x05) => Instruction::LdVxI,
+            (0x0f, _, 0x0f, 0x08) => Instruction::Pitch,
+            (0x0f, _, 0x0f, 0x0b) => Instruction::Input,
+            _ => Instruction::Invalid,
+        }
+    }
+}
+
+// Vollständig dekodierte Instruktion mit ihren Operanden. Wird pro Adresse zwischengespeichert,
+// damit Opcodes bei erneuter Ausführung weder aus dem RAM geholt noch dekodiert werden müssen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    pub instruction: Instruction,
+    pub opcode: u16,
+    // Register Vx und Vy
+    pub x: u8,
+    pub y: u8,
+}
+
+impl Decoded {
+    pub fn new(opcode: u16) -> Self {
+        Decoded {
+            instruction: Instruction::decode(opcode),
+            opcode,
+            x: ((opcode & 0x0F00) >> 8) as u8,
+            y: ((opcode & 0x00F0) >> 4) as u8,
+        }
+    }
+
+    // Adresse (die unteren 12 Bit)
+    pub fn nnn(&self) -> usize {
+        usize::from(self.opcode & 0x0FFF)
+    }
+
+    // Byte (die unteren 8 Bit)
+    pub fn kk(&self) -> u8 {
+        self.opcode as u8
+    }
+
+    // Nibble (die unteren 4 Bit)
+    pub fn n(&self) -> usize {
+        usize::from(self.opcode & 0x000F)
+    }
+}
@@ -0,0 +1,280 @@
+//! Speicheraufbau der emulierten Maschine: Größe des RAMs, Ladeadresse, Start des PCs und Tiefe des Stacks
+//!
+//! Die Werte werden beim Erstellen des `Processor` festgelegt. Mit Heap wird der RAM genau so
+//! groß angelegt wie konfiguriert (bis 16 MB, mehr kann I mit den 24 Bit von MegaChip nicht
+//! adressieren), ohne Heap steht höchstens der Standardspeicher von 4 KB zur Verfügung.
+
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use super::{DISPLAY_HEIGHT, MAX_DISPLAY_HEIGHT, RAM_SIZE, STACK_SIZE};
+use crate::prelude::*;
+
+// Größter möglicher RAM
+#[cfg(feature = "alloc")]
+pub const MAX_RAM_SIZE: usize = 0x1000000;
+#[cfg(not(feature = "alloc"))]
+pub const MAX_RAM_SIZE: usize = RAM_SIZE;
+// Größte mögliche Tiefe des Stacks
+pub const MAX_STACK_DEPTH: usize = 64;
+
+// Bereiche des Interpreters im RAM des COSMAC VIP: Der Stack wächst von 0xECF abwärts
+// (höherwertiges Byte zuerst), V0 bis VF liegen ab 0xEF0 und das Display (8 Bytes pro
+// Zeile, x = 0 im höchstwertigen Bit) ab 0xF00
+pub const VIP_STACK: usize = 0xEA0;
+pub const VIP_STACK_END: usize = 0xED0;
+pub const VIP_REGISTERS: usize = 0xEF0;
+pub const VIP_DISPLAY: usize = 0xF00;
+pub const VIP_MEMORY_END: usize = 0x1000;
+
+// Hires CHIP-8: ROMs beginnen mit einem Sprung auf 0x260 (1260), das Programm selbst
+// startet hinter dem mitgelieferten Interpreter bei 0x2C0
+pub const HIRES_SIGNATURE: [u8; 2] = [0x12, 0x60];
+pub const HIRES_START: u16 = 0x2C0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineError {
+    // Der RAM ist größer als `MAX_RAM_SIZE` oder zu klein für die Schrift bzw. die Bereiche des VIP
+    RamSize(usize),
+    // Die Ladeadresse liegt außerhalb des RAMs
+    LoadAddress(u16),
+    // Der PC startet außerhalb des RAMs
+    InitialPc(u16),
+    // Der Stack ist leer oder tiefer als `MAX_STACK_DEPTH` (beim VIP als sein Bereich im RAM)
+    StackDepth(usize),
+    // Die Variante braucht mehr RAM (MegaChip adressiert mit I bis zu 16 MB)
+    Variant(Variant),
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MachineError::RamSize(size) => write!(f, "Unsupported RAM size: {} bytes", size),
+            MachineError::LoadAddress(address) => write!(f, "The load address {:#05X} is outside of the RAM", address),
+            MachineError::InitialPc(address) => write!(f, "The initial PC {:#05X} is outside of the RAM", address),
+            MachineError::StackDepth(depth) => write!(f, "Unsupported stack depth: {}", depth),
+            MachineError::Variant(variant) => write!(f, "The RAM is too small for {:?}", variant),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MachineError {}
+
+#[cfg(feature = "alloc")]
+impl From<MachineError> for JsValue {
+    fn from(error: MachineError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Die ROM passt ab der Ladeadresse nicht in den RAM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadError {
+    pub size: usize,
+    // Freier Speicher ab der Ladeadresse
+    pub available: usize,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The ROM is too large: {} bytes, but only {} bytes are available", self.size, self.available)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {}
+
+#[cfg(feature = "alloc")]
+impl From<LoadError> for JsValue {
+    fn from(error: LoadError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Erweiterungen des Befehlssatzes
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    // CHIP-8 des COSMAC VIP
+    Chip8,
+    // CHIP-8X: Farbkarte (02A0, BXYN), zweites Keypad (EXF2, EXF5), Tonhöhe (FXF8) und Eingabe (FXFB)
+    Chip8x,
+    // Hires CHIP-8 mit zwei Seiten Bildspeicher: 64x64 Pixel, 0230 löscht das Display
+    Hires,
+    // MegaChip8: 0011 schaltet einen Bildspeicher mit 256x192 Pixeln und 256 Farben ein, I hat
+    // 24 Bit (nur mit Heap)
+    MegaChip,
+}
+
+impl Variant {
+    // Höhe des Displays in Pixeln
+    pub fn display_height(self) -> usize {
+        match self {
+            Variant::Hires => MAX_DISPLAY_HEIGHT,
+            _ => DISPLAY_HEIGHT,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MachineConfig {
+    // Größe des RAMs in Bytes
+    pub ram_size: usize,
+    // Adresse, an die `load` die ROM schreibt
+    pub load_address: u16,
+    // Startwert des PCs nach `reset` und `load`
+    pub initial_pc: u16,
+    // Anzahl verschachtelter Aufrufe (2NNN)
+    pub stack_depth: usize,
+    // Stack, Register und Display werden wie beim VIP in den RAM ab 0xEA0 gespiegelt
+    pub vip_memory: bool,
+    // 0NNN ruft Unterroutinen in Maschinensprache des CDP1802 auf, ansonsten ist 0NNN ungültig
+    pub machine_code: bool,
+}
+
+#[wasm_bindgen]
+impl MachineConfig {
+    // COSMAC VIP mit 4 KB: Programme beginnen bei 0x200
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        MachineConfig {
+            ram_size: RAM_SIZE,
+            load_address: 0x200,
+            initial_pc: 0x200,
+            stack_depth: STACK_SIZE,
+            vip_memory: false,
+            machine_code: false,
+        }
+    }
+
+    // COSMAC VIP mit Stack, Registern und Display im RAM, bis zu 12 verschachtelten Aufrufen
+    // und Unterroutinen in Maschinensprache (für hybride Programme)
+    pub fn vip() -> Self {
+        MachineConfig {
+            stack_depth: 12,
+            vip_memory: true,
+            machine_code: true,
+            ..MachineConfig::new()
+        }
+    }
+
+    // ETI-660: Programme beginnen bei 0x600
+    pub fn eti660() -> Self {
+        MachineConfig {
+            load_address: 0x600,
+            initial_pc: 0x600,
+            ..MachineConfig::new()
+        }
+    }
+
+    // CHIP-8X: Der größere Interpreter belegt den Speicher bis 0x2FF
+    pub fn chip8x() -> Self {
+        MachineConfig {
+            load_address: 0x300,
+            initial_pc: 0x300,
+            ..MachineConfig::new()
+        }
+    }
+
+    // Hires CHIP-8 (64x64): Die ROM enthält den angepassten Interpreter ab 0x200,
+    // das eigentliche Programm beginnt bei 0x2C0
+    pub fn hires() -> Self {
+        MachineConfig {
+            initial_pc: HIRES_START,
+            ..MachineConfig::new()
+        }
+    }
+
+    // MegaChip8: Der ganze mit 24 Bit adressierbare Speicher für Grafiken und Samples
+    #[cfg(feature = "alloc")]
+    pub fn megachip() -> Self {
+        MachineConfig {
+            ram_size: MAX_RAM_SIZE,
+            ..MachineConfig::new()
+        }
+    }
+
+    // Freier Speicher ab der Ladeadresse (beim VIP bis zum Stack)
+    pub fn available(&self) -> usize {
+        let end = if self.vip_memory { VIP_STACK } else { self.ram_size };
+        end.saturating_sub(usize::from(self.load_address))
+    }
+}
+
+// Fehler lassen sich nur mit Heap als JavaScript-Wert übergeben
+#[cfg_attr(feature = "alloc", wasm_bindgen)]
+impl MachineConfig {
+    pub fn validate(&self) -> Result<(), MachineError> {
+        let min_size = if self.vip_memory { VIP_MEMORY_END } else { 0x200 };
+        if self.ram_size > MAX_RAM_SIZE || self.ram_size < min_size {
+            return Err(MachineError::RamSize(self.ram_size));
+        }
+        if usize::from(self.load_address) >= self.ram_size {
+            return Err(MachineError::LoadAddress(self.load_address));
+        }
+        if usize::from(self.initial_pc) + 1 >= self.ram_size {
+            return Err(MachineError::InitialPc(self.initial_pc));
+        }
+        let max_depth = if self.vip_memory { (VIP_STACK_END - VIP_STACK) / 2 } else { MAX_STACK_DEPTH };
+        if self.stack_depth == 0 || self.stack_depth > max_depth {
+            return Err(MachineError::StackDepth(self.stack_depth));
+        }
+        Ok(())
+    }
+
+    // Ob der RAM groß genug für die Variante ist: MegaChip braucht den von `megachip()`
+    pub fn check_variant(&self, variant: Variant) -> Result<(), MachineError> {
+        if variant == Variant::MegaChip && self.ram_size < MAX_RAM_SIZE {
+            return Err(MachineError::Variant(variant));
+        }
+        Ok(())
+    }
+}
+
+impl Default for MachineConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Speicher mit einem Eintrag pro Adresse. Mit Heap genau so groß wie konfiguriert, ohne Heap
+// ein Feld mit `RAM_SIZE` Einträgen, von dem nur der vordere Teil genutzt wird
+pub(crate) struct Memory<T> {
+    #[cfg(feature = "alloc")]
+    data: Vec<T>,
+    #[cfg(not(feature = "alloc"))]
+    data: [T; RAM_SIZE],
+    size: usize,
+}
+
+impl<T: Copy> Memory<T> {
+    pub(crate) fn new(value: T, size: usize) -> Self {
+        Memory {
+            #[cfg(feature = "alloc")]
+            data: alloc::vec![value; size],
+            #[cfg(not(feature = "alloc"))]
+            data: [value; RAM_SIZE],
+            size,
+        }
+    }
+}
+
+impl<T> Deref for Memory<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data[..self.size]
+    }
+}
+
+impl<T> DerefMut for Memory<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.data[..self.size]
+    }
+}
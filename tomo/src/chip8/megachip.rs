@@ -0,0 +1,246 @@
+//! MegaChip8: Bildspeicher mit 256x192 Pixeln, 256 Farben und digitalisiertem Ton
+//!
+//! Im MegaChip-Modus (0011) zeichnet DXYN Sprites aus Farbindizes (`03NN` x `04NN` Bytes ab I,
+//! Index 0 ist durchsichtig) mit der Palette aus 02NN in einen eigenen Bildspeicher. 00E0 zeigt
+//! diesen an und leert ihn danach, sichtbar ist also immer der zuletzt fertige Frame. Kollisionen
+//! gibt es nur mit Pixeln im Index aus 09NN. Der normale 64x32 `Display` bleibt davon unberührt.
+
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+
+pub const MEGACHIP_WIDTH: usize = 256;
+pub const MEGACHIP_HEIGHT: usize = 192;
+// Kopf eines digitalisierten Tons: Abtastrate (2 Bytes), Länge (3 Bytes) und ein freies Byte
+pub const SOUND_HEADER_SIZE: usize = 6;
+
+// Ob ein 0NNN-Opcode im MegaChip-Modus eine eigene Bedeutung hat: Scrollen (00BN, 00CN, 00FB,
+// 00FC), I mit 24 Bit (01NN NNNN), Palette (02NN), Spritegröße (03NN, 04NN), Helligkeit (05NN),
+// Ton (060N, 0700), Mischmodus (080N) und Kollisionsfarbe (09NN)
+pub fn is_megachip_opcode(opcode: u16) -> bool {
+    match opcode >> 8 {
+        0x00 => matches!(opcode & 0xF0, 0xB0 | 0xC0) || opcode == 0x00FB || opcode == 0x00FC,
+        0x01..=0x05 | 0x09 => true,
+        0x06 | 0x08 => opcode & 0xF0 == 0,
+        0x07 => opcode == 0x0700,
+        _ => false,
+    }
+}
+
+// Wie neue Pixel mit dem Bildspeicher gemischt werden (080N)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    // Die neue Farbe deckt zu 25, 50 bzw. 75 Prozent
+    Alpha25,
+    Alpha50,
+    Alpha75,
+    // Farbkanäle werden addiert bzw. multipliziert
+    Add,
+    Multiply,
+}
+
+impl BlendMode {
+    pub fn from_nibble(value: u8) -> Option<BlendMode> {
+        [BlendMode::Normal, BlendMode::Alpha25, BlendMode::Alpha50, BlendMode::Alpha75, BlendMode::Add, BlendMode::Multiply]
+            .get(usize::from(value))
+            .copied()
+    }
+
+    // Mischt `color` über `below` (jeweils 0xRRGGBB)
+    fn apply(self, color: u32, below: u32) -> u32 {
+        let [_, r1, g1, b1] = color.to_be_bytes();
+        let [_, r2, g2, b2] = below.to_be_bytes();
+        let mix = |a: u8, b: u8| -> u8 {
+            let (a, b) = (u32::from(a), u32::from(b));
+            match self {
+                BlendMode::Normal => a as u8,
+                BlendMode::Alpha25 => ((a + 3 * b) / 4) as u8,
+                BlendMode::Alpha50 => ((a + b) / 2) as u8,
+                BlendMode::Alpha75 => ((3 * a + b) / 4) as u8,
+                BlendMode::Add => (a + b).min(0xFF) as u8,
+                BlendMode::Multiply => (a * b / 0xFF) as u8,
+            }
+        };
+        u32::from_be_bytes([0, mix(r1, r2), mix(g1, g2), mix(b1, b2)])
+    }
+}
+
+// Digitalisierter Ton (060N), 8 Bit ohne Vorzeichen und mono
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigitizedSound {
+    pub sample_rate: u16,
+    // Wird wiederholt, bis 0700 ihn anhält
+    pub looping: bool,
+    samples: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl DigitizedSound {
+    pub fn samples(&self) -> Vec<u8> {
+        self.samples.clone()
+    }
+}
+
+impl DigitizedSound {
+    // Liest Kopf und Samples ab `data`, zu lange Längenangaben werden auf die Daten gekürzt
+    pub fn parse(data: &[u8], looping: bool) -> Option<DigitizedSound> {
+        let header = data.get(..SOUND_HEADER_SIZE)?;
+        let sample_rate = u16::from_be_bytes([header[0], header[1]]);
+        let length = u32::from_be_bytes([0, header[2], header[3], header[4]]) as usize;
+        let samples = &data[SOUND_HEADER_SIZE..];
+        Some(DigitizedSound {
+            sample_rate,
+            looping,
+            samples: samples[..length.min(samples.len())].to_vec(),
+        })
+    }
+
+    pub fn sample_slice(&self) -> &[u8] {
+        &self.samples
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MegaChip {
+    // Farben je Index (0xRRGGBB), Index 0 ist durchsichtig
+    palette: [u32; 256],
+    pub sprite_width: usize,
+    pub sprite_height: usize,
+    // Helligkeit des angezeigten Bildes (05NN), 0xFF ist voll sichtbar
+    pub alpha: u8,
+    pub blend: BlendMode,
+    // Farbindex, mit dem DXYN Kollisionen meldet (09NN), 0 meldet keine
+    pub collision_color: u8,
+    // Bildspeicher, in den gezeichnet wird: Farbindizes für Kollisionen und gemischte Farben
+    indices: Vec<u8>,
+    pixels: Vec<u32>,
+    // Zuletzt mit 00E0 angezeigter Frame
+    frame: Vec<u32>,
+    changed: bool,
+    pub sound: Option<DigitizedSound>,
+}
+
+impl MegaChip {
+    pub fn new() -> Self {
+        let size = MEGACHIP_WIDTH * MEGACHIP_HEIGHT;
+        MegaChip {
+            palette: [0; 256],
+            sprite_width: 8,
+            sprite_height: 8,
+            alpha: 0xFF,
+            blend: BlendMode::Normal,
+            collision_color: 0,
+            indices: alloc::vec![0; size],
+            pixels: alloc::vec![0; size],
+            frame: alloc::vec![0; size],
+            changed: false,
+            sound: None,
+        }
+    }
+
+    // 02NN: Farben als ARGB (4 Bytes je Farbe) ab Index 1, der Alphakanal wird ignoriert
+    pub fn load_palette(&mut self, data: &[u8]) {
+        for (index, color) in data.chunks_exact(4).take(255).enumerate() {
+            self.palette[index + 1] = u32::from_be_bytes([0, color[1], color[2], color[3]]);
+        }
+    }
+
+    pub fn palette(&self, index: u8) -> u32 {
+        self.palette[usize::from(index)]
+    }
+
+    // DXYN: Zeichnet `sprite_width` x `sprite_height` Farbindizes ab (`x`, `y`), Pixel außerhalb
+    // werden abgeschnitten. Gibt zurück, ob ein Pixel in der Kollisionsfarbe übermalt wurde
+    pub fn draw_sprite(&mut self, x: usize, y: usize, sprite: &[u8]) -> bool {
+        let mut collision = false;
+        for (row, line) in sprite.chunks(self.sprite_width).take(self.sprite_height).enumerate() {
+            let py = y + row;
+            if py >= MEGACHIP_HEIGHT {
+                break;
+            }
+            for (column, index) in line.iter().enumerate() {
+                let px = x + column;
+                if px >= MEGACHIP_WIDTH {
+                    break;
+                }
+                if *index == 0 {
+                    continue;
+                }
+                let offset = py * MEGACHIP_WIDTH + px;
+                collision |= self.collision_color != 0 && self.indices[offset] == self.collision_color;
+                self.indices[offset] = *index;
+                self.pixels[offset] = self.blend.apply(self.palette[usize::from(*index)], self.pixels[offset]);
+            }
+        }
+        collision
+    }
+
+    // Größe eines Sprites in Bytes
+    pub fn sprite_size(&self) -> usize {
+        self.sprite_width * self.sprite_height
+    }
+
+    // 00E0: Zeigt den Bildspeicher mit der eingestellten Helligkeit an und leert ihn
+    pub fn present(&mut self) {
+        let alpha = u32::from(self.alpha);
+        for (target, color) in self.frame.iter_mut().zip(&self.pixels) {
+            let [_, r, g, b] = color.to_be_bytes();
+            let fade = |value: u8| (u32::from(value) * alpha / 0xFF) as u8;
+            *target = u32::from_be_bytes([0, fade(r), fade(g), fade(b)]);
+        }
+        self.indices.fill(0);
+        self.pixels.fill(0);
+        self.changed = true;
+    }
+
+    // 00BN / 00CN: Verschiebt den Bildspeicher um `lines` Zeilen nach oben bzw. unten
+    pub fn scroll_vertical(&mut self, lines: isize) {
+        self.scroll(lines * MEGACHIP_WIDTH as isize);
+    }
+
+    // 00FB / 00FC: Verschiebt den Bildspeicher um `columns` Pixel nach rechts bzw. links
+    pub fn scroll_horizontal(&mut self, columns: isize) {
+        for row in 0..MEGACHIP_HEIGHT {
+            let range = row * MEGACHIP_WIDTH..(row + 1) * MEGACHIP_WIDTH;
+            shift(&mut self.indices[range.clone()], columns);
+            shift(&mut self.pixels[range], columns);
+        }
+    }
+
+    fn scroll(&mut self, offset: isize) {
+        shift(&mut self.indices, offset);
+        shift(&mut self.pixels, offset);
+    }
+
+    // Angezeigter Frame, 0xRRGGBB je Pixel und zeilenweise
+    pub fn frame(&self) -> &[u32] {
+        &self.frame
+    }
+
+    pub fn take_changed(&mut self) -> bool {
+        core::mem::replace(&mut self.changed, false)
+    }
+}
+
+impl Default for MegaChip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Verschiebt die Werte um `offset` Stellen nach hinten (negativ: nach vorne), frei gewordene
+// Stellen werden 0
+fn shift<T: Copy + Default>(values: &mut [T], offset: isize) {
+    let distance = offset.unsigned_abs().min(values.len());
+    let len = values.len();
+    if offset > 0 {
+        values.copy_within(..len - distance, distance);
+        values[..distance].fill(T::default());
+    } else {
+        values.copy_within(distance.., 0);
+        values[len - distance..].fill(T::default());
+    }
+}
@@ -1,14 +1,38 @@
+#[cfg(feature = "alloc")]
+pub mod analyzer;
+pub mod cdp1802;
+pub mod color;
+pub mod controller;
 mod display;
+pub mod events;
+pub mod font;
+pub mod instruction;
+pub mod machine;
+#[cfg(feature = "alloc")]
+pub mod megachip;
 pub mod processor;
+pub mod quirks;
+#[cfg(feature = "alloc")]
+pub mod recorder;
+#[cfg(feature = "alloc")]
+pub mod render;
+#[cfg(feature = "alloc")]
+pub mod screenshot;
+
+pub use display::{Colors, Display};
 
 // Konstanten
+// Höhe des Displays, Hires CHIP-8 verdoppelt sie auf `MAX_DISPLAY_HEIGHT`
 pub const DISPLAY_HEIGHT: usize = 32;
+pub const MAX_DISPLAY_HEIGHT: usize = 64;
 pub const DISPLAY_WIDTH: usize = 64;
 
 const RAM_SIZE: usize = 4096;
 pub const OPCODE_SIZE: usize = 2;
 const REGISTER_SIZE: usize = 18;
 const STACK_SIZE: usize = 16;
+// Instruktionen pro Frame, wenn nichts anderes bekannt ist
+pub const DEFAULT_TICK_RATE: u16 = 12;
 
 pub const FONT: [u8; 80] = [
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
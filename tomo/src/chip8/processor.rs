@@ -1,16 +1,62 @@
-use crate::{err, log};
-use crate::chip8::display::Display;
+use crate::{debug, error, info, trace};
+#[cfg(any(feature = "octo", feature = "alloc"))]
+use crate::warn;
+use crate::chip8::display::{Colors, Display};
+use crate::chip8::events::{Event, EventKind, EventSet};
+use crate::chip8::font::{Font, FontError};
+#[cfg(feature = "alloc")]
+use crate::chip8::font::FontSet;
+#[cfg(feature = "alloc")]
+use crate::chip8::events::EventHandler;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use crate::chip8::cdp1802::Cdp1802;
+use crate::chip8::color::ColorLayer;
+use crate::chip8::instruction::{Decoded, Instruction};
+#[cfg(feature = "alloc")]
+use crate::chip8::analyzer::{InstructionSet, RomAnalysis, MIN_CONFIDENCE};
+#[cfg(feature = "alloc")]
+use crate::chip8::megachip::{is_megachip_opcode, BlendMode, DigitizedSound, MegaChip};
+use crate::chip8::machine::{
+    LoadError, MachineConfig, MachineError, Memory, Variant, HIRES_SIGNATURE, HIRES_START, MAX_STACK_DEPTH, VIP_DISPLAY, VIP_MEMORY_END, VIP_REGISTERS, VIP_STACK, VIP_STACK_END,
+};
+use crate::chip8::quirks::Quirks;
+#[cfg(feature = "database")]
+use crate::database::{RomDatabase, RomInfo};
+#[cfg(feature = "octo")]
+use crate::octo::{Cartridge, CartridgeError, OctoOptions, OptionsError};
+use crate::platform::{default_platform, PlatformHandle};
+// Ohne Heap ist die Plattform kein Trait-Objekt, daher muss der Trait importiert sein
+#[cfg(not(feature = "alloc"))]
+use crate::platform::Platform;
 use crate::prelude::*;
+use crate::reporting::target;
 
-use super::{DISPLAY_HEIGHT, DISPLAY_WIDTH, FONT, OPCODE_SIZE, RAM_SIZE, REGISTER_SIZE, STACK_SIZE};
+use super::{DEFAULT_TICK_RATE, DISPLAY_HEIGHT, DISPLAY_WIDTH, OPCODE_SIZE, REGISTER_SIZE};
+
+// Takt der Tonausgabe von CHIP-8X, die Frequenz ist `SOUND_CLOCK / (Tonhöhe + 1)`
+const SOUND_CLOCK: f32 = 27535.0;
+// Tonhöhe nach dem Einschalten (etwa 213 Hz)
+const DEFAULT_PITCH: u8 = 0x80;
+
+// Höchstens so viele Instruktionen darf eine Unterroutine in Maschinensprache ausführen
+const MACHINE_CODE_LIMIT: u32 = 100_000;
+// Der PC ist 16 Bit breit, darüber hinaus liegen keine Instruktionen im Cache
+#[cfg(feature = "alloc")]
+const MAX_CACHE_SIZE: usize = 0x10000;
 
 // Zähler, der nach jedem Fetch bestimmt, worauf der PC gestellt werden muss
 #[derive(Debug)]
 enum ProgramCounter {
     Next,
     Skip,
+    // PC bleibt stehen, bis eine Taste gedrückt wird
     Block,
+    // PC bleibt stehen, die Instruktion wird erneut ausgeführt
+    Wait,
     Jump(usize),
+    // Das Programm ist beendet, der PC bleibt stehen
+    Halt(HaltReason),
 }
 
 impl ProgramCounter {
@@ -27,12 +73,119 @@ impl ProgramCounter {
 pub struct Output {
     pub success: bool,
     pub opcode: u16,
+    // Gesetzt, sobald das Programm beendet ist
+    pub halt: Option<HaltReason>,
+}
+
+// Grund, warum ein Programm als beendet gilt
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HaltReason {
+    // 1NNN springt auf sich selbst, es passiert nie wieder etwas
+    SelfJump,
+    // SCHIP 00FD (EXIT)
+    Exit,
+    // Der PC ist über das Ende des RAMs hinausgelaufen
+    EndOfMemory,
+    // Eine Unterroutine in Maschinensprache (0NNN) ist nicht zurückgekehrt
+    MachineCode,
+    // 2NNN bei vollem Stack
+    StackOverflow,
+    // 00EE bei leerem Stack
+    StackUnderflow,
+    // DXYN, FX33, FX55 oder FX65 greifen über I hinter das Ende des RAMs zu
+    InvalidAddress,
+}
+
+// Grund, warum `run` bzw. `run_until` angehalten hat
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    // Alle angeforderten Instruktionen wurden ausgeführt
+    Cycles,
+    // Die Bedingung von `run_until` ist eingetreten
+    Condition,
+    // FX0A wartet auf eine Taste
+    WaitingForKey,
+    // DXYN wartet auf den nächsten Frame (VBlank-Quirk)
+    VBlank,
+    // Ein ungültiger Opcode wurde ausgeführt
+    InvalidOpcode,
+    // Das Programm ist beendet (siehe `RunResult::halt`)
+    Halted,
+}
+
+// Zusammenfassung mehrerer ausgeführter Instruktionen
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunResult {
+    // Anzahl ausgeführter Instruktionen
+    pub cycles: u32,
+    pub stop: StopReason,
+    // Zuletzt ausgeführter Opcode
+    pub opcode: u16,
+    // Ob sich das Display verändert hat
+    pub display_changed: bool,
+    // Ob der Piep-Ton gerade läuft
+    pub sound_active: bool,
+    // Ob der CPU auf eine Taste wartet
+    pub waiting_for_key: bool,
+    // Warum das Programm beendet ist, falls es beendet ist
+    pub halt: Option<HaltReason>,
+    // Anzahl der Instruktionen, die als Leerlauf übersprungen wurden (in `cycles` enthalten).
+    // Übersprungen wird höchstens der Rest von `cycles`, nicht bis zum nächsten Timer oder Tastendruck
+    pub idle_cycles: u32,
+}
+
+// Zusammenfassung eines Frames (siehe `run_frame`)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameResult {
+    // Anzahl ausgeführter Instruktionen, davon als Leerlauf übersprungen
+    pub cycles: u32,
+    pub idle_cycles: u32,
+    pub stop: StopReason,
+    pub display_changed: bool,
+    pub sound_started: bool,
+    pub sound_stopped: bool,
+    pub sound_active: bool,
+    pub key_wait_started: bool,
+    pub key_wait_ended: bool,
+    pub waiting_for_key: bool,
+    // Der ungültige Opcode, falls einer ausgeführt wurde
+    pub invalid_opcode: Option<u16>,
+    pub halt: Option<HaltReason>,
+}
+
+// Zustand des CPU zu Beginn einer Schleife, um Leerlauf zu erkennen: Wiederholt sich der
+// Zustand ohne Nebenwirkungen, wiederholt sich auch jeder weitere Durchlauf identisch
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct IdleState {
+    pc: u16,
+    i_reg: u32,
+    sp: u16,
+    registers: [u8; REGISTER_SIZE],
+    stack: [u16; MAX_STACK_DEPTH],
+    effects: u32,
+}
+
+// Einstellungen, die beim Laden einer ROM ersetzt wurden (`detect`, `apply_rom_info`). Sie werden
+// beim nächsten `load` wiederhergestellt, damit sie nicht auf die nächste ROM übergehen
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Default)]
+struct Replaced {
+    variant: Option<Variant>,
+    quirks: Option<Quirks>,
+    machine_code: Option<bool>,
+    font: Option<Font>,
+    tick_rate: Option<u16>,
+    colors: Option<Colors>,
 }
 
 // Tasten als Klassen-Repräsentation
 #[wasm_bindgen]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Key {
     K1,
     K2,
@@ -82,16 +235,24 @@ pub enum Register {
 
 #[wasm_bindgen]
 pub struct Processor {
-    // RAM / Speicher des CHIP8. Besteht aus 4kb
-    ram: [u8; RAM_SIZE],
+    // RAM / Speicher des CHIP8. Besteht standardmäßig aus 4kb
+    ram: Memory<u8>,
+    // Bereits dekodierte Instruktionen je Adresse, wird bei Schreibzugriffen
+    // auf die betroffenen Adressen verworfen (selbstmodifizierender Code).
+    // Reicht nur so weit wie der PC (16 Bit), nicht über den ganzen RAM. Nur mit Heap, damit
+    // `no_std`-Ziele ohne Heap keinen statischen Speicher dafür brauchen
+    #[cfg(feature = "alloc")]
+    cache: Memory<Option<Decoded>>,
     // "Program Counter" (Programmzähler) Pointer der
     // auf die aktuelle Instruktion im Speicher zeigt
     pub pc: u16,
     // "Index register" Pointer der auf Stellen im Speicher zeigt
-    pub i_reg: u16,
+    // (24 Bit bei MegaChip, sonst 16 Bit)
+    pub i_reg: u32,
     // "Stack" 16-bit Adressen für Funktionen, auf die der CPU
     // zurückgreifen kann um Rückgaben zu erhalten
-    stack: [u16; STACK_SIZE],
+    // Genutzt werden nur die ersten `stack_depth` Einträge
+    stack: [u16; MAX_STACK_DEPTH],
     // "Stack pointer" Pointer der auf Stellen im Stack
     pub sp: u16,
     // Register, um alles mögliche des Programmes (Wie Variablen) zu speichern
@@ -104,8 +265,79 @@ pub struct Processor {
     pub display: Display,
     // Speicher für gedrückte Tasten
     keys: [bool; 16],
+    // Tasten des zweiten Keypads (CHIP-8X)
+    second_keys: [bool; 16],
     // Speichert die zuletzt gedrückte Taste
     pub current_key: Option<Key>,
+    // Verhaltensunterschiede der Interpreter. Mit `set_quirks` gesetzt ersetzt `load` sie nicht
+    pub quirks: Quirks,
+    // Erweiterungen des Befehlssatzes. Mit `set_variant` gesetzt ersetzt `load` sie nicht
+    pub variant: Variant,
+    // Farben der CHIP-8X Farbkarte
+    color_layer: ColorLayer,
+    // Zuletzt mit FXF8 ausgegebene Tonhöhe (CHIP-8X)
+    pitch: u8,
+    // Bildspeicher, Palette und Ton von MegaChip, solange der MegaChip-Modus an ist
+    #[cfg(feature = "alloc")]
+    megachip: Option<MegaChip>,
+    // Empfohlene Anzahl an Instruktionen pro Frame (60 Hz)
+    pub tick_rate: u16,
+    // Empfohlene Farben für die Darstellung
+    pub colors: Colors,
+    // Schrift für FX29 / FX30, wird bei `reset` und `load` neu geschrieben
+    font: Font,
+    // Empfohlene Drehung der Anzeige im Uhrzeigersinn in Grad
+    pub screen_rotation: u16,
+    // Ob seit dem letzten Zeichnen ein neuer Frame begonnen hat (VBlank-Quirk)
+    vblank: bool,
+    // Worauf die zuletzt ausgeführte Instruktion wartet, falls sie blockiert
+    blocked: Option<StopReason>,
+    // Gesetzt, sobald das Programm beendet ist, bis zum nächsten `reset` bzw. `load`
+    halted: Option<HaltReason>,
+    // Speicheraufbau, wird beim Erstellen festgelegt
+    machine: MachineConfig,
+    // CPU für Unterroutinen in Maschinensprache (0NNN)
+    cpu: Cdp1802,
+    // Ob `run` Leerlauf-Schleifen (z.B. Warten auf den Delay-Timer) überspringt. Das ist nur eine
+    // Näherung: Es entfällt die restliche Ausführung des aktuellen Aufrufs, es wird aber nicht bis
+    // zum Ablauf des Timers oder zur nächsten Eingabe vorgespult. Standardmäßig aus
+    pub fast_forward: bool,
+    // Zähler für Nebenwirkungen (RAM, Display, Zufall, Ton), die eine Schleife
+    // vom Leerlauf unterscheiden
+    effects: u32,
+    // Ob Einstellungen erkannter ROMs automatisch übernommen werden
+    #[cfg(feature = "database")]
+    pub auto_configure: bool,
+    // Ob `load` Variante, Quirks und Schrift für ROMs außerhalb der Datenbank anhand
+    // ihrer Instruktionen wählt
+    #[cfg(feature = "alloc")]
+    pub auto_detect: bool,
+    // Befehlssatz der zuletzt geladenen ROM
+    #[cfg(feature = "alloc")]
+    rom_analysis: Option<RomAnalysis>,
+    // Ob Variante bzw. Quirks von Hand gewählt wurden und daher nicht erkannt werden
+    #[cfg(feature = "alloc")]
+    variant_chosen: bool,
+    #[cfg(feature = "alloc")]
+    quirks_chosen: bool,
+    // Für die zuletzt geladene ROM ersetzte Einstellungen
+    #[cfg(feature = "alloc")]
+    replaced: Replaced,
+    // Informationen zur zuletzt geladenen ROM, falls diese erkannt wurde
+    #[cfg(feature = "database")]
+    rom_info: Option<RomInfo>,
+    // Eigene Datenbank, ansonsten wird die eingebettete genutzt
+    #[cfg(feature = "database")]
+    database: Option<std::rc::Rc<RomDatabase>>,
+    // Host-Plattform für Zufall, Diagnose, Ton und Zeit
+    platform: PlatformHandle,
+    // Ob der Plattform zuletzt ein laufender Piep-Ton gemeldet wurde
+    beeping: bool,
+    // Empfänger für Ereignisse
+    #[cfg(feature = "alloc")]
+    events: Option<Box<dyn EventHandler>>,
+    // Seit Beginn des Frames gemeldete Ereignisse
+    frame_events: EventSet,
 }
 
 #[wasm_bindgen]
@@ -113,406 +345,1433 @@ impl Processor {
     // Konstruktor
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
+        Self::with_platform(default_platform())
+    }
+
+    // Opcode aus dem RAM holen
+    pub fn fetch(&mut self) -> u16 {
+        let pc = self.pc as usize;
+        u16::from(self.ram[pc]) << 8 | u16::from(self.ram[pc + 1])
+    }
+
+    // Ausführen des Opcodes
+    pub fn execute(&mut self, opcode: u16) -> Output {
+        self.execute_instruction(Decoded::new(opcode))
+    }
+
+    // Wird genutzt, um die Schrift in den RAM zu laden
+    fn initialize(&mut self) {
+        let font = self.font;
+        for (address, glyphs) in [(font.address, font.small_glyphs()), (font.big_address, font.big_glyphs())] {
+            let start = usize::from(address);
+            self.ram[start..start + glyphs.len()].copy_from_slice(glyphs);
+            self.invalidate(start, start + glyphs.len());
+        }
+    }
+
+    pub fn font(&self) -> Font {
+        self.font
+    }
+
+    pub fn machine(&self) -> MachineConfig {
+        self.machine
+    }
+
+    // Wählt die Quirks von Hand, `load` behält sie dann für alle ROMs bei
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+        #[cfg(feature = "alloc")]
+        {
+            self.quirks_chosen = true;
+            self.replaced.quirks = None;
+        }
+    }
+
+    pub fn tick(&mut self) -> Output {
+        self.tick_timers();
+        self.step()
+    }
+
+    // Warum das Programm beendet ist, falls es beendet ist
+    pub fn halted(&self) -> Option<HaltReason> {
+        self.halted
+    }
+
+    // Beginnt einen neuen Frame (60 Hz): Verringert die Timer und beendet den VBlank
+    pub fn tick_timers(&mut self) {
+        if self.registers[Register::DT as usize] > 0 {
+            self.registers[Register::DT as usize] -= 1;
+        }
+
+        if self.registers[Register::ST as usize] > 0 {
+            self.registers[Register::ST as usize] -= 1;
+        }
+        self.update_audio();
+        self.vblank = true;
+    }
+
+    // Führt bis zu `cycles` Instruktionen am Stück aus, ohne die Timer zu verändern.
+    // Hält früher an, wenn der CPU wartet oder ein ungültiger Opcode auftritt
+    // Mit `fast_forward` werden Leerlauf-Schleifen ohne Nebenwirkungen für den Rest von
+    // `cycles` übersprungen
+    pub fn run(&mut self, cycles: u32) -> RunResult {
+        self.execute_cycles(cycles, |_| false, self.fast_forward)
+    }
+
+    // Führt einen ganzen Frame aus: `tick_timers` und danach `run(tick_rate)`
+    pub fn run_frame(&mut self) -> FrameResult {
+        self.begin_frame();
+        self.tick_timers();
+        let result = self.run(u32::from(self.tick_rate));
+        self.end_frame(result)
+    }
+
+    // Ob der CPU (FX0A) auf eine Taste wartet
+    pub fn waiting_for_key(&self) -> bool {
+        self.blocked == Some(StopReason::WaitingForKey)
+    }
+
+    // Boolean, ob ein Piep-Ton gespielt werden soll
+    pub fn should_beep(&self) -> bool {
+        self.registers[Register::ST as usize] > 0
+    }
+
+    // Taste gedrückt
+    pub fn key_press(&mut self, key: Key) {
+        debug!(self.platform, target::INPUT, "Key {:?} pressed", key);
+        self.current_key = Some(key);
+        self.keys[key as usize] = true;
+    }
+
+    // Taste losgelassen
+    pub fn key_up(&mut self, key: Key) {
+        debug!(self.platform, target::INPUT, "Key {:?} released", key);
+        if let Some(current_key) = self.current_key {
+            if key == current_key {
+                self.current_key = None;
+            }
+        }
+        self.keys[key as usize] = false;
+    }
+
+    // Taste des zweiten Keypads (CHIP-8X) gedrückt bzw. losgelassen
+    pub fn second_key_press(&mut self, key: Key) {
+        debug!(self.platform, target::INPUT, "Key {:?} pressed on the second keypad", key);
+        self.second_keys[key as usize] = true;
+    }
+
+    pub fn second_key_up(&mut self, key: Key) {
+        debug!(self.platform, target::INPUT, "Key {:?} released on the second keypad", key);
+        self.second_keys[key as usize] = false;
+    }
+
+    pub fn color_layer(&self) -> ColorLayer {
+        self.color_layer
+    }
+
+    // Frequenz des Tons in Hz, bei CHIP-8X über FXF8 einstellbar
+    pub fn sound_frequency(&self) -> f32 {
+        SOUND_CLOCK / (f32::from(self.pitch) + 1.0)
+    }
+
+    pub fn reset(&mut self) {
+        // Speicher leeren
+        self.ram.fill(0);
+        #[cfg(feature = "alloc")]
+        self.cache.fill(None);
+
+        // Stack leeren
+        self.stack = [0; MAX_STACK_DEPTH];
+
+        // Register leeren
+        self.registers = [0; REGISTER_SIZE];
+        self.sp = 0;
+        self.pc = self.initial_pc();
+        self.halted = None;
+        self.blocked = None;
+        self.update_audio();
+
+        // Display leeren, die Höhe richtet sich nach der Variante
+        self.display.set_height(self.variant.display_height());
+        self.color_layer = ColorLayer::new();
+        self.pitch = DEFAULT_PITCH;
+        #[cfg(feature = "alloc")]
+        {
+            self.megachip = None;
+        }
+
+        // Schrift neu hinzufügen
+        self.initialize();
+    }
+
+    // Setzt den Startwert des Zufallsgenerators (z.B. für reproduzierbare Läufe
+    // oder auf Zielen ohne Entropiequelle)
+    pub fn seed_random(&mut self, seed: u32) {
+        self.platform.seed(seed);
+    }
+
+    pub fn test_set_registers(&mut self, idx: usize, data: u8) {
+        self.registers[idx] = data;
+    }
+
+    pub fn test_get_registers(&mut self, idx: usize) -> u8 {
+        self.registers[idx]
+    }
+
+    pub fn test_set_ram(&mut self, idx: usize, data: u8) {
+        self.write_ram(idx, data);
+    }
+
+    pub fn test_get_ram(&mut self, idx: usize) -> u8 {
+        self.ram[idx]
+    }
+
+    pub fn test_get_stack(&mut self, idx: usize) -> u16 {
+        self.stack[idx]
+    }
+
+    pub fn test_set_stack(&mut self, idx: usize, data: u16) {
+        self.stack[idx] = data;
+    }
+}
+
+// Fehler lassen sich nur mit Heap als JavaScript-Wert übergeben
+#[cfg_attr(feature = "alloc", wasm_bindgen)]
+impl Processor {
+    // Konstruktor mit eigenem Speicheraufbau
+    pub fn with_machine(machine: MachineConfig) -> Result<Processor, MachineError> {
+        Self::with_machine_and_platform(machine, default_platform())
+    }
+
+    // Wählt die Variante von Hand, `load` behält sie dann für alle ROMs bei.
+    // MegaChip braucht den RAM von `MachineConfig::megachip()`
+    pub fn set_variant(&mut self, variant: Variant) -> Result<(), MachineError> {
+        self.machine.check_variant(variant)?;
+        self.variant = variant;
+        #[cfg(feature = "alloc")]
+        {
+            self.variant_chosen = true;
+            self.replaced.variant = None;
+        }
+        Ok(())
+    }
+
+    // Wechselt die Schrift und schreibt sie sofort in den Speicher
+    pub fn set_font(&mut self, font: Font) -> Result<(), FontError> {
+        font.validate(self.ram.len())?;
+        self.font = font;
+        #[cfg(feature = "alloc")]
+        {
+            self.replaced.font = None;
+        }
+        self.initialize();
+        Ok(())
+    }
+
+    // Laden von Daten in den CPU | Nimmt einen Slice, damit kein Heap benötigt wird
+    pub fn load(&mut self, data: &[u8]) -> Result<usize, LoadError> {
+        let available = self.machine.available();
+        if data.len() > available {
+            error!(self.platform, target::LOADER, "The ROM ({} bytes) does not fit into the ram ({} bytes available)", data.len(), available);
+            return Err(LoadError { size: data.len(), available });
+        }
+        #[cfg(feature = "alloc")]
+        self.restore_replaced();
+        // Hires CHIP-8 erkennt man am Sprung über den mitgelieferten Interpreter
+        if self.variant == Variant::Chip8 && self.machine.load_address == 0x200 && data.starts_with(&HIRES_SIGNATURE) {
+            info!(self.platform, target::LOADER, "Detected a hires ROM, switching to 64x64 pixels");
+            #[cfg(feature = "alloc")]
+            {
+                self.replaced.variant = Some(self.variant);
+            }
+            self.variant = Variant::Hires;
+        }
+        self.reset();
+        let start = usize::from(self.machine.load_address);
+        self.ram[start..start + data.len()].copy_from_slice(data);
+        info!(self.platform, target::LOADER, "Data was successfully loaded into the ram with a size of {} bytes.", data.len());
+        #[cfg(feature = "database")]
+        self.identify(data);
+        #[cfg(feature = "alloc")]
+        self.detect(data);
+        Ok(data.len())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[wasm_bindgen]
+impl Processor {
+    // Ob der MegaChip-Modus (0011) eingeschaltet ist
+    pub fn megachip_enabled(&self) -> bool {
+        self.megachip.is_some()
+    }
+
+    // Ergebnis der Analyse der zuletzt geladenen ROM
+    pub fn rom_analysis(&self) -> Option<RomAnalysis> {
+        self.rom_analysis
+    }
+
+    // Zuletzt mit 060N gestarteter digitalisierter Ton, bis 0700 ihn anhält
+    pub fn digitized_sound(&self) -> Option<DigitizedSound> {
+        self.megachip.as_ref().and_then(|megachip| megachip.sound.clone())
+    }
+}
+
+impl Processor {
+    // Konstruktor mit eigener Host-Plattform (z.B. `TestPlatform`)
+    pub fn with_platform(platform: PlatformHandle) -> Self {
+        Self::build(MachineConfig::new(), platform)
+    }
+
+    pub fn with_machine_and_platform(machine: MachineConfig, platform: PlatformHandle) -> Result<Self, MachineError> {
+        machine.validate()?;
+        Ok(Self::build(machine, platform))
+    }
+
+    fn build(machine: MachineConfig, platform: PlatformHandle) -> Self {
         // Error Hook-Initialisieren
         #[cfg(feature = "console_error_panic_hook")]
         console_error_panic_hook::set_once();
 
         let mut proc = Processor {
-            ram: [0; RAM_SIZE],
+            ram: Memory::new(0, machine.ram_size),
+            #[cfg(feature = "alloc")]
+            cache: Memory::new(None, machine.ram_size.min(MAX_CACHE_SIZE)),
             // PC muss auf den Hex-Wert von 512 gesetzt werden,
             // da alle Werte darunter im Speicher ursprünglich
             // für den Interpreter genutzt wurden
             // (Entfällt im Emulator aber)
-            pc: 0x200,
+            pc: machine.initial_pc,
             i_reg: 0,
-            stack: [0; STACK_SIZE],
+            stack: [0; MAX_STACK_DEPTH],
             sp: 0,
             registers: [0; REGISTER_SIZE],
             display: Display::new(),
             keys: [false; 16],
+            second_keys: [false; 16],
             current_key: None,
+            quirks: Quirks::new(),
+            variant: Variant::Chip8,
+            color_layer: ColorLayer::new(),
+            pitch: DEFAULT_PITCH,
+            #[cfg(feature = "alloc")]
+            megachip: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            colors: Colors::default(),
+            font: Font::new(),
+            screen_rotation: 0,
+            vblank: true,
+            blocked: None,
+            halted: None,
+            machine,
+            cpu: Cdp1802::new(),
+            fast_forward: false,
+            effects: 0,
+            #[cfg(feature = "database")]
+            auto_configure: true,
+            #[cfg(feature = "alloc")]
+            auto_detect: true,
+            #[cfg(feature = "alloc")]
+            rom_analysis: None,
+            #[cfg(feature = "alloc")]
+            variant_chosen: false,
+            #[cfg(feature = "alloc")]
+            quirks_chosen: false,
+            #[cfg(feature = "alloc")]
+            replaced: Replaced::default(),
+            #[cfg(feature = "database")]
+            rom_info: None,
+            #[cfg(feature = "database")]
+            database: None,
+            platform,
+            beeping: false,
+            #[cfg(feature = "alloc")]
+            events: None,
+            frame_events: EventSet::default(),
         };
 
         proc.initialize();
         proc
     }
 
-    // Opcode aus dem RAM holen
-    pub fn fetch(&mut self) -> u16 {
-        let pc = self.pc as usize;
-        u16::from(self.ram[pc]) << 8 | u16::from(self.ram[pc + 1])
+    // Wie `run`, hält aber zusätzlich an, sobald `condition` nach einer Instruktion zutrifft
+    // (Leerlauf wird hier nie übersprungen, damit `condition` jede Instruktion sieht)
+    pub fn run_until<F: FnMut(&Processor) -> bool>(&mut self, cycles: u32, condition: F) -> RunResult {
+        self.execute_cycles(cycles, condition, false)
     }
 
-    // Ausführen des Opcodes
-    pub fn execute(&mut self, opcode: u16) -> Output {
-        // Opcode auftrennen in verschiedene Nibbles, Register und Instruktionen
-        let nibbles = (
-            (opcode & 0xF000) >> 12 as u8,
-            (opcode & 0x0F00) >> 8 as u8,
-            (opcode & 0x00F0) >> 4 as u8,
-            (opcode & 0x000F) as u8,
-        );
-        let nnn = (opcode & 0x0FFF) as usize;
-        let kk = (opcode & 0x00FF) as u8;
-        let x = nibbles.1 as usize;
-        let y = nibbles.2 as usize;
-        let n = nibbles.3 as usize;
+    fn execute_cycles<F: FnMut(&Processor) -> bool>(&mut self, cycles: u32, mut condition: F, fast_forward: bool) -> RunResult {
+        self.take_display_changed();
+        let mut result = RunResult {
+            cycles: 0,
+            stop: StopReason::Cycles,
+            opcode: 0,
+            display_changed: false,
+            sound_active: false,
+            waiting_for_key: false,
+            halt: None,
+            idle_cycles: 0,
+        };
+        // Letzter Rücksprung mit dem Zustand danach und der Anzahl Instruktionen bis dahin
+        let mut probe: Option<(u16, IdleState, u32)> = None;
+        while result.cycles < cycles {
+            if !self.running() {
+                break;
+            }
+            let pc = self.pc;
+            let output = self.step();
+            result.cycles += 1;
+            result.opcode = output.opcode;
+            if fast_forward && output.opcode & 0xF000 == 0x1000 && self.pc <= pc {
+                let state = self.idle_state();
+                match probe {
+                    Some((jump, previous, at)) if jump == pc && previous == state => {
+                        // Alle ganzen Durchläufe bis zum Ende überspringen, der Rest wird normal ausgeführt
+                        let length = result.cycles - at;
+                        let skipped = (cycles - result.cycles) / length * length;
+                        result.cycles += skipped;
+                        result.idle_cycles += skipped;
+                        probe = None;
+                    }
+                    _ => probe = Some((pc, state, result.cycles)),
+                }
+            }
+            if !output.success {
+                result.stop = StopReason::InvalidOpcode;
+                break;
+            }
+            if let Some(reason) = self.blocked {
+                result.stop = reason;
+                break;
+            }
+            if condition(self) {
+                result.stop = StopReason::Condition;
+                break;
+            }
+        }
+        if self.halted.is_some() {
+            result.stop = StopReason::Halted;
+        }
+        result.display_changed = self.take_display_changed();
+        if result.display_changed {
+            self.emit(Event::DisplayChanged);
+        }
+        result.sound_active = self.should_beep();
+        result.waiting_for_key = self.waiting_for_key();
+        result.halt = self.halted;
+        result
+    }
+
+    // Zustand des MegaChip-Modus, falls eingeschaltet
+    #[cfg(feature = "alloc")]
+    pub fn megachip(&self) -> Option<&MegaChip> {
+        self.megachip.as_ref()
+    }
+
+    // Ob sich das Bild (Display bzw. der Frame von MegaChip) seit dem letzten Aufruf verändert hat
+    fn take_display_changed(&mut self) -> bool {
+        let changed = self.display.take_changed();
+        #[cfg(feature = "alloc")]
+        let changed = changed | self.megachip.as_mut().is_some_and(MegaChip::take_changed);
+        changed
+    }
+
+    fn idle_state(&self) -> IdleState {
+        IdleState {
+            pc: self.pc,
+            i_reg: self.i_reg,
+            sp: self.sp,
+            registers: self.registers,
+            stack: self.stack,
+            effects: self.effects,
+        }
+    }
+
+    // Setzt den Empfänger für Ereignisse (z.B. eine Closure `|event| ...`)
+    #[cfg(feature = "alloc")]
+    pub fn set_event_handler(&mut self, handler: Box<dyn EventHandler>) {
+        self.events = Some(handler);
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn clear_event_handler(&mut self) {
+        self.events = None;
+    }
+
+    // Tauscht die Host-Plattform aus
+    pub fn set_platform(&mut self, platform: PlatformHandle) {
+        self.platform = platform;
+    }
+
+    // Monotone Uhr der Plattform in Mikrosekunden
+    pub fn now_micros(&self) -> u64 {
+        self.platform.now_micros()
+    }
+
+    // Führt die Instruktion am PC aus. Ist sie im Cache (nur mit Heap), wird sie weder geholt
+    // noch dekodiert
+    fn step(&mut self) -> Output {
+        if !self.running() {
+            return Output {
+                success: true,
+                opcode: 0,
+                halt: self.halted,
+            };
+        }
+        #[cfg(feature = "alloc")]
+        let decoded = match self.cache[self.pc as usize] {
+            Some(decoded) => decoded,
+            None => {
+                let decoded = Decoded::new(self.fetch());
+                self.cache[self.pc as usize] = Some(decoded);
+                decoded
+            }
+        };
+        #[cfg(not(feature = "alloc"))]
+        let decoded = Decoded::new(self.fetch());
+        self.execute_instruction(decoded)
+    }
+
+    // Schreibt ein Byte in den RAM und verwirft betroffene Instruktionen im Cache
+    fn write_ram(&mut self, address: usize, value: u8) {
+        self.ram[address] = value;
+        self.invalidate(address, address + 1);
+    }
+
+    // Wird nach jedem Schreibzugriff auf den Bereich `start..end` aufgerufen
+    fn invalidate(&mut self, start: usize, end: usize) {
+        self.effects = self.effects.wrapping_add(1);
+        #[cfg(feature = "alloc")]
+        self.clear_cache(start, end);
+        if self.machine.vip_memory {
+            self.mirror_from_ram(start, end);
+        }
+    }
+
+    // Verwirft alle Instruktionen, die den Bereich `start..end` berühren. Eine Instruktion
+    // ist 2 Byte lang, daher ist auch die Adresse vor dem Bereich betroffen
+    #[cfg(feature = "alloc")]
+    fn clear_cache(&mut self, start: usize, end: usize) {
+        let end = end.min(self.cache.len());
+        for entry in &mut self.cache[start.saturating_sub(1).min(end)..end] {
+            *entry = None;
+        }
+    }
+
+    // Start des Programms: Hires CHIP-8 beginnt hinter dem Interpreter in der ROM, sofern
+    // die Konfiguration nicht schon einen anderen Start als 0x200 vorgibt
+    fn initial_pc(&self) -> u16 {
+        if self.variant == Variant::Hires && self.machine.initial_pc == 0x200 {
+            HIRES_START
+        } else {
+            self.machine.initial_pc
+        }
+    }
+
+    // VIP-Speicher: Schreibt die Register, den Stack und (falls `display`) das Display in den RAM.
+    // Das zählt nicht als Nebenwirkung, da sich nur bereits bekannter Zustand widerspiegelt
+    fn mirror_to_ram(&mut self, display: bool) {
+        for index in 0..16 {
+            self.mirror_byte(VIP_REGISTERS + index, self.registers[index]);
+        }
+        for index in 0..self.machine.stack_depth {
+            let [high, low] = self.stack[index].to_be_bytes();
+            self.mirror_byte(VIP_STACK_END - 2 - 2 * index, high);
+            self.mirror_byte(VIP_STACK_END - 1 - 2 * index, low);
+        }
+        if display {
+            for y in 0..DISPLAY_HEIGHT {
+                for (index, byte) in self.display.row(y).to_be_bytes().iter().enumerate() {
+                    self.mirror_byte(VIP_DISPLAY + y * 8 + index, *byte);
+                }
+            }
+        }
+    }
+
+    fn mirror_byte(&mut self, address: usize, value: u8) {
+        if self.ram[address] != value {
+            self.ram[address] = value;
+            #[cfg(feature = "alloc")]
+            self.clear_cache(address, address + 1);
+        }
+    }
+
+    // VIP-Speicher: Übernimmt Schreibzugriffe des Programms auf die Bereiche des Interpreters
+    fn mirror_from_ram(&mut self, start: usize, end: usize) {
+        for address in start.max(VIP_STACK)..end.min(VIP_MEMORY_END) {
+            match address {
+                VIP_REGISTERS..=0xEFF => self.registers[address - VIP_REGISTERS] = self.ram[address],
+                VIP_DISPLAY..=0xFFF => {
+                    let y = (address - VIP_DISPLAY) / 8;
+                    let mut row = [0; 8];
+                    row.copy_from_slice(&self.ram[VIP_DISPLAY + y * 8..VIP_DISPLAY + y * 8 + 8]);
+                    self.display.set_row(y, u64::from_be_bytes(row));
+                }
+                _ if address < VIP_STACK_END => {
+                    let index = (VIP_STACK_END - 1 - address) / 2;
+                    if index < self.machine.stack_depth {
+                        let low = VIP_STACK_END - 1 - 2 * index;
+                        self.stack[index] = u16::from_be_bytes([self.ram[low - 1], self.ram[low]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Führt eine bereits dekodierte Instruktion aus
+    fn execute_instruction(&mut self, decoded: Decoded) -> Output {
+        let Decoded { instruction, opcode, .. } = decoded;
+        let (nnn, kk, n) = (decoded.nnn(), decoded.kk(), decoded.n());
+        let (x, y) = (usize::from(decoded.x), usize::from(decoded.y));
 
         let mut success: bool = true;
 
-        let pc_change: ProgramCounter = match nibbles {
-            (0x00, 0x00, 0x0e, 0x00) => {
+        let pc_change: ProgramCounter = match instruction {
+            #[cfg(feature = "alloc")]
+            Instruction::Cls if self.megachip.is_some() => {
+                // MegaChip: Zeigt den fertigen Frame an und leert den Bildspeicher
+                if let Some(megachip) = self.megachip.as_mut() {
+                    megachip.present();
+                }
+                self.effects = self.effects.wrapping_add(1);
+                ProgramCounter::Next
+            }
+            #[cfg(feature = "alloc")]
+            Instruction::Sys if self.variant == Variant::MegaChip && (opcode == 0x0010 || opcode == 0x0011) => {
+                // MegaChip: 0011 schaltet den MegaChip-Modus ein, 0010 wieder aus. Ohne
+                // ausreichend RAM (siehe `MachineConfig::megachip`) ist 0011 ungültig
+                if opcode == 0x0011 {
+                    if let Err(error) = self.machine.check_variant(Variant::MegaChip) {
+                        error!(self.platform, target::CPU, "Cannot enable the MegaChip mode: {}", error);
+                        self.emit(Event::InvalidOpcode(opcode));
+                        success = false;
+                    } else if self.megachip.is_none() {
+                        self.megachip = Some(MegaChip::new());
+                    }
+                } else {
+                    self.megachip = None;
+                }
+                debug!(self.platform, target::DISPLAY, "MegaChip mode {}", if self.megachip.is_some() { "enabled" } else { "disabled" });
+                self.effects = self.effects.wrapping_add(1);
+                ProgramCounter::Next
+            }
+            #[cfg(feature = "alloc")]
+            Instruction::Sys if self.megachip.is_some() && is_megachip_opcode(opcode) => self.execute_megachip(opcode, kk),
+            Instruction::Cls => {
                 // CLS: Display leeren
                 self.display.cls();
+                self.effects = self.effects.wrapping_add(1);
+                trace!(self.platform, target::DISPLAY, "Display cleared");
                 ProgramCounter::Next
             }
-            (0x00, 0x00, 0x0e, 0x0e) => {
+            Instruction::Sys if self.variant == Variant::Hires && opcode == 0x0230 => {
+                // Hires CHIP-8: Der angepasste Interpreter löscht beide Seiten des Displays
+                self.display.cls();
+                self.effects = self.effects.wrapping_add(1);
+                trace!(self.platform, target::DISPLAY, "Display cleared");
+                ProgramCounter::Next
+            }
+            Instruction::Ret => {
                 // RET: Rückgabe einer Subroutine
-                self.sp -= 1;
-                ProgramCounter::Jump(self.stack[self.sp as usize] as usize)
+                if self.sp == 0 {
+                    ProgramCounter::Halt(HaltReason::StackUnderflow)
+                } else {
+                    self.sp -= 1;
+                    ProgramCounter::Jump(self.stack[self.sp as usize] as usize)
+                }
+            }
+            Instruction::Exit if self.machine.machine_code && self.variant != Variant::MegaChip => {
+                // SYS 0FD: Ohne SCHIP-Erweiterungen ist 00FD ein Aufruf in Maschinensprache
+                self.call_machine_code(nnn, x, y)
+            }
+            Instruction::Exit => {
+                // EXIT: Beendet das Programm (SCHIP)
+                ProgramCounter::Halt(HaltReason::Exit)
             }
-            (0x01, _, _, _) => {
+            Instruction::Sys if self.variant == Variant::Chip8x && opcode == 0x02A0 => {
+                // CHIP-8X: Schaltet die Hintergrundfarbe weiter
+                self.color_layer.next_background();
+                self.effects = self.effects.wrapping_add(1);
+                ProgramCounter::Next
+            }
+            Instruction::Sys if self.machine.machine_code => {
+                // SYS <addr>: Ruft eine Unterroutine in Maschinensprache des CDP1802 auf
+                self.call_machine_code(nnn, x, y)
+            }
+            Instruction::Jp => {
                 // JP <addr>: Springen zur gegebenen Adresse
-                ProgramCounter::Jump(nnn)
+                // (Ein Sprung auf sich selbst beendet das Programm)
+                if nnn == self.pc as usize {
+                    ProgramCounter::Halt(HaltReason::SelfJump)
+                } else {
+                    ProgramCounter::Jump(nnn)
+                }
             }
-            (0x02, _, _, _) => {
+            Instruction::Call => {
                 // CALL <addr>: Ruft die Subroutine an gegebener
                 // Adresse auf
-                self.stack[self.sp as usize] = (self.pc as usize + OPCODE_SIZE) as u16;
-                self.sp += 1;
-                ProgramCounter::Jump(nnn)
+                if usize::from(self.sp) >= self.machine.stack_depth {
+                    ProgramCounter::Halt(HaltReason::StackOverflow)
+                } else {
+                    self.stack[self.sp as usize] = (self.pc as usize + OPCODE_SIZE) as u16;
+                    self.sp += 1;
+                    ProgramCounter::Jump(nnn)
+                }
             }
-            (0x03, _, _, _) => {
+            Instruction::SeByte => {
                 // SE (Vx, Kk): Überspringen der nächsten Instruktion,
                 // wenn Vx == Kk
                 ProgramCounter::skip_if(self.registers[x] == kk)
             }
-            (0x04, _, _, _) => {
+            Instruction::SneByte => {
                 // SNE (Vx, Kk): Überspringen der nächsten Instruktion,
                 // wenn Vx != Kk
                 ProgramCounter::skip_if(self.registers[x] != kk)
             }
-            (0x05, _, _, 0x00) => {
+            Instruction::SeReg => {
                 // SE (Vx, Vy): Überspringen der nächsten Instruktion,
                 // wenn Vx == Vy
                 ProgramCounter::skip_if(self.registers[x] == self.registers[y])
             }
-            (0x06, _, _, _) => {
+            Instruction::LdByte => {
                 // LD (Vx, Kk): Setzt das Register von Vx auf  Kk-Bytes
                 self.registers[x] = kk;
                 ProgramCounter::Next
             }
-            (0x07, _, _, _) => {
+            Instruction::AddByte => {
                 // ADD (Vx, Kk): Addiert Kk auf den Wert des Registers
                 // Vx und speicher dies dort
-                self.registers[x] += kk as u8;
+                self.registers[x] = self.registers[x].wrapping_add(kk);
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x00) => {
+            Instruction::LdReg => {
                 // LD (Vx, Vy): Setzen des Registers von Vx auf
                 // den Wer des Registers von Vy
                 self.registers[x] = self.registers[y];
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x01) => {
+            Instruction::Or => {
                 // OR (Vx, Vy): Bit-OR Operation zwischen Register
                 // Vx und Vy mit speicherung des Wertes in Vx
                 self.registers[x] |= self.registers[y];
+                self.logic_quirk();
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x02) => {
+            Instruction::And => {
                 // AND (Vx, Vy): Bit-AND Operation zwischen Register
                 // Vx und Vy mit speicherung des Wertes in Vx
                 self.registers[x] &= self.registers[y];
+                self.logic_quirk();
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x03) => {
+            Instruction::Xor => {
                 // XOR (Vx, Vy): Bit-XOR Operation zwischen Register
                 // Vx und Vy mit speicherung des Wertes in Vx
                 self.registers[x] ^= self.registers[y];
+                self.logic_quirk();
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x04) => {
+            Instruction::AddReg => {
                 // ADD (Vx, Vy): Addieren des Register Wertes von Vy
                 // und Vx und speicherung des Wertes in Vx
                 // Wenn Vx größer als u8 ist, wird VF auf 1 gesetzt,
                 // andernfalls auf 0 und es wird nur die u8 Form der
                 // Zahl gespeichert
-                let vx = self.registers[x] as u16;
-                let vy = self.registers[y] as u16;
-                let result = vx + vy;
-                self.registers[x] = result as u8;
-                self.registers[Register::VF as usize] = if result > 0xFF { 1 } else { 0 };
+                let (result, carry) = self.registers[x].overflowing_add(self.registers[y]);
+                self.write_carry(x, result, carry);
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x05) => {
+            Instruction::Sub => {
                 // SUB (Vx, Vy): Subtrahieren des Register Wertes von Vy
                 // und Vx und speicherung des Wertes in Vx
                 // Wenn Vy > Vx, dann VF 1, andernfalls 0
-                self.registers[Register::VF as usize] = if self.registers[x] > self.registers[y] { 1 } else { 0 };
-                self.registers[x] = self.registers[x].wrapping_sub(self.registers[y]);
+                let flag = self.registers[x] > self.registers[y];
+                self.write_carry(x, self.registers[x].wrapping_sub(self.registers[y]), flag);
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x06) => {
+            Instruction::Shr => {
                 // SHR (Vx): Wenn das unbedeutendste Bit von VX 1 ist, wird VF auf 1
                 // gesetzt, ansonsten 0 und Vx wird durch 2 geteilt
-                self.registers[Register::VF as usize] = self.registers[x] & 1;
-                self.registers[x] >>= 1;
+                // (Ohne Shift-Quirk wird Vy verschoben und in Vx gespeichert)
+                let source = if self.quirks.shift { self.registers[x] } else { self.registers[y] };
+                self.write_carry(x, source >> 1, source & 1 == 1);
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x07) => {
+            Instruction::Subn => {
                 // SUBN (Vx, Vy): Vx wird zu Vy minus Vx
                 // Wenn Vy > Vx, dann VF 1, andernfalls 0
-                self.registers[Register::VF as usize] = if self.registers[y] > self.registers[x] { 1 } else { 0 };
-                self.registers[x] = self.registers[y].wrapping_sub(self.registers[x]);
+                let flag = self.registers[y] > self.registers[x];
+                self.write_carry(x, self.registers[y].wrapping_sub(self.registers[x]), flag);
                 ProgramCounter::Next
             }
-            (0x08, _, _, 0x0e) => {
+            Instruction::Shl => {
                 // SHL (Vx): If das wichtigste Bit von Vx 1 ist, wird VF auf 1 gesetzt,NB
                 // ansonsten 0 und Vx wird um 2 multipliziert
-                let msb = (self.registers[x] & 0b1000_0000) >> 7;
-                self.registers[Register::VF as usize] = msb;
-                self.registers[x] <<= 1;
+                let source = if self.quirks.shift { self.registers[x] } else { self.registers[y] };
+                self.write_carry(x, source << 1, source & 0b1000_0000 != 0);
                 ProgramCounter::Next
             }
-            (0x09, _, _, 0x00) => {
+            Instruction::SneReg => {
                 // SNE (Vx, Vy): Überspringen der nächsten Instruktion,
                 // wenn Vx != Vy
                 ProgramCounter::skip_if(self.registers[x] != self.registers[y])
             }
-            (0x0a, _, _, _) => {
+            Instruction::LdI => {
                 // LD (I_reg) <addr>: Verschiebt das Index-Register auf
                 // die gegebene Adresse
-                self.i_reg = nnn as u16;
+                self.i_reg = nnn as u32;
+                ProgramCounter::Next
+            }
+            Instruction::JpOffset if self.variant == Variant::Chip8x => {
+                // CHIP-8X BXYN: Färbt mit VY grobe Blöcke (N = 0, Position und Größe in VX und VX+1)
+                // bzw. N Zeilen eines Blocks ab der Pixelposition VX, VX+1
+                let (horizontal, vertical, color) = (self.registers[x], self.registers[(x + 1) & 0x0F], self.registers[y]);
+                match n {
+                    0 => self.color_layer.fill_zones(horizontal, vertical, color),
+                    rows => self.color_layer.fill_rows(horizontal, vertical, rows as u8, color),
+                }
+                self.effects = self.effects.wrapping_add(1);
                 ProgramCounter::Next
             }
-            (0x0b, _, _, _) => {
+            Instruction::JpOffset => {
                 // JP (V0) <addr>: Spingt zur Adresse (V0 + Adresse)
-                ProgramCounter::Jump(nnn + self.registers[Register::V0 as usize] as usize)
+                // (Mit Jump-Quirk wird stattdessen Vx genutzt)
+                let offset = if self.quirks.jump { self.registers[x] } else { self.registers[Register::V0 as usize] };
+                ProgramCounter::Jump(nnn + offset as usize)
             }
-            (0x0c, _, _, _) => {
+            Instruction::Rnd => {
                 // RND (Vx, Kk): Generiert eine zufällige Zahl zwischen 0 und 255
                 // Welche über den Bit-AND Operator mit Kk verschmolzen wird und
                 // im Register von Vx gespeichert wird
-                self.registers[x] = random_byte() & kk;
+                self.registers[x] = self.platform.random_byte() & kk;
+                self.effects = self.effects.wrapping_add(1);
                 ProgramCounter::Next
             }
-            (0x0d, _, _, _) => {
-                // DRW (Vx, Vy, n): Liest n-Bytes aus dem RAM mit dem Startpunkt im
-                // Index-Register. DIe Bytes werden dann als "Sprite" auf dem Bildschirm
-                // an der Stelle (Vx | Vy) dargestellt. Wenn an der Stelle ein Pixel
-                // gelöscht wird, wird das VF Register auf 1 gestellt, ansonsten 0
-                self.registers[Register::VF as usize] = 0;
-                for byte in 0..n {
-                    let y = (self.registers[y] as usize + byte) % DISPLAY_HEIGHT;
-                    for bit in 0..8 {
-                        let x = (self.registers[x] as usize + bit) % DISPLAY_WIDTH;
-                        let color = (self.ram[self.i_reg as usize + byte] >> (7 - bit)) & 1;
-                        self.registers[Register::VF as usize] |= u8::from(color & (if self.display.get_pixel(y, x) { 1 } else { 0 }));
-                        self.display.set_pixel(y, x, ((if self.display.get_pixel(y, x) { 1 } else { 0 }) ^ color) == 1);
-                    }
-                }
-
+            Instruction::Drw if self.quirks.vblank && !self.vblank => {
+                // Mit VBlank-Quirk wird nur einmal pro Frame gezeichnet
+                ProgramCounter::Wait
+            }
+            #[cfg(feature = "alloc")]
+            Instruction::Drw if self.megachip.is_some() => self.draw_megachip(x, y),
+            Instruction::Drw => self.draw(opcode, x, y, n),
+            Instruction::AddNibbles if self.variant == Variant::Chip8x => {
+                // CHIP-8X: Addiert beide Hälften von Vx und Vy getrennt, jeweils modulo 8
+                let (a, b) = (self.registers[x], self.registers[y]);
+                let (high, low) = (((a >> 4) + (b >> 4)) % 8, ((a & 0x0F) + (b & 0x0F)) % 8);
+                self.registers[x] = high << 4 | low;
                 ProgramCounter::Next
             }
-            (0x0e, _, 0x09, 0x0e) => {
+            Instruction::SkpSecond if self.variant == Variant::Chip8x => {
+                ProgramCounter::skip_if(self.second_keys[usize::from(self.registers[x] & 0x0F)])
+            }
+            Instruction::SknpSecond if self.variant == Variant::Chip8x => {
+                ProgramCounter::skip_if(!self.second_keys[usize::from(self.registers[x] & 0x0F)])
+            }
+            Instruction::Pitch if self.variant == Variant::Chip8x => {
+                // CHIP-8X: Gibt Vx als Tonhöhe aus
+                self.pitch = self.registers[x];
+                ProgramCounter::Next
+            }
+            Instruction::Input if self.variant == Variant::Chip8x => {
+                // CHIP-8X: Liest vom I/O-Port, an dem hier kein Gerät hängt
+                self.registers[x] = 0;
+                ProgramCounter::Next
+            }
+            Instruction::Skp => {
                 // SKP (Vx): Überspringt die nächste Instruktion, wenn die
                 // korrespondierende Taste gedrückt ist
                 let key = self.registers[x];
                 ProgramCounter::skip_if(self.keys[key as usize])
             }
-            (0x0e, _, 0x0a, 0x01) => {
+            Instruction::Sknp => {
                 // SKNP (Vx): Überspringt nächste Instruktion, wenn die
                 // korrespondierende Taste nicht gedrückt ist
                 let key = self.registers[x];
                 ProgramCounter::skip_if(!self.keys[key as usize])
             }
-            (0x0f, _, 0x00, 0x07) => {
+            Instruction::LdVxDt => {
                 // LD (Vx, DT): Setzt das Vx-Register zu dem Wert des Delay-Timers
                 self.registers[x] = self.registers[Register::DT as usize];
                 ProgramCounter::Next
             }
-            (0x0f, _, 0x00, 0x0a) => {
+            Instruction::LdVxK => {
                 // LD (Vx, K): Wenn kein Knopf gedrückt wurde, "blockiert" der CPU
-                // bis die richtige Taste gedrückt wurde, indem wir 2 Opcodes zurückspringen
-                // und wieder hier landen
-                let res = match self.current_key {
+                // bis die richtige Taste gedrückt wurde, indem der PC stehen bleibt
+                // und die Instruktion erneut ausgeführt wird
+                match self.current_key {
                     Some(key) => {
                         self.registers[x] = key as u8;
                         ProgramCounter::Next
                     }
                     None => ProgramCounter::Block
-                };
-                res
+                }
             }
-            (0x0f, _, 0x01, 0x05) => {
+            Instruction::LdDtVx => {
                 // LD (DTm Vx): Setzt den Delay-Timer auf den Wert des Registers von Vx
                 self.registers[Register::DT as usize] = self.registers[x];
                 ProgramCounter::Next
             }
-            (0x0f, _, 0x01, 0x08) => {
+            Instruction::LdStVx => {
                 // LD (ST, Vx): Setzt den Sound-Timer auf den Wert des Registers von Vx
                 self.registers[Register::ST as usize] = self.registers[x];
+                self.update_audio();
+                self.effects = self.effects.wrapping_add(1);
                 ProgramCounter::Next
             }
-            (0x0f, _, 0x01, 0x0e) => {
+            Instruction::AddI => {
                 // ADD (I_reg, Vx): Index Register wird um den Wer des Vx Registers erhöht
-                self.i_reg += u16::from(self.registers[x]);
+                self.i_reg += u32::from(self.registers[x]);
                 self.registers[Register::VF as usize] = if self.i_reg > 0x0F00 { 1 } else { 0 };
                 ProgramCounter::Next
             }
-            (0x0f, _, 0x02, 0x09) => {
+            Instruction::LdF => {
                 // LD (F, Vx): Index Register wird auf den Hex Wer (Darum *5) für die Position
                 // eines Sprite aus dem Wert des Vx Registers gestellt
-                self.i_reg = (self.registers[x] * 5) as u16;
+                self.i_reg = u32::from(self.font.address) + u32::from(self.registers[x] & 0x0F) * 5;
+                ProgramCounter::Next
+            }
+            Instruction::LdHf if self.font.has_big() => {
+                // LD (HF, Vx): Wie FX29, aber für die große Schrift (10 Bytes pro Ziffer)
+                self.i_reg = u32::from(self.font.big_address) + u32::from(self.registers[x] & 0x0F) * 10;
                 ProgramCounter::Next
             }
-            (0x0f, _, 0x03, 0x03) => {
+            Instruction::LdB => {
                 // LD (B, Vx): Nimmt den Dezimalwert von Vx und Platziert
                 // - Hunderterstelle im RAM an Stelle Index Register
                 // - Zehnerstelle im RAM an stelle Index Register +1
                 // - Einerstelle im RAM an Stelle Index Register +2
-                self.ram[self.i_reg as usize] = self.registers[x] / 100;
-                self.ram[(self.i_reg + 1) as usize] = (self.registers[x] % 100) / 10;
-                self.ram[(self.i_reg + 2) as usize] = self.registers[x] % 10;
-                ProgramCounter::Next
+                match self.i_range(3) {
+                    Some(start) => {
+                        self.write_ram(start, self.registers[x] / 100);
+                        self.write_ram(start + 1, (self.registers[x] % 100) / 10);
+                        self.write_ram(start + 2, self.registers[x] % 10);
+                        ProgramCounter::Next
+                    }
+                    None => self.invalid_address(opcode, 3),
+                }
             }
-            (0x0f, _, 0x05, 0x05) => {
-                // LD (I, Vx): Kopiert alle Register in den RAM mit Startpunkt im Index Register
-                for i in 0..REGISTER_SIZE {
-                    self.ram[(self.i_reg as usize + i) as usize] = self.registers[i];
+            Instruction::LdIVx => {
+                // LD (I, Vx): Kopiert die Register V0 bis Vx in den RAM mit Startpunkt im Index Register
+                match self.i_range(x + 1) {
+                    Some(start) => {
+                        self.ram[start..=start + x].copy_from_slice(&self.registers[..=x]);
+                        self.invalidate(start, start + x + 1);
+                        self.memory_quirk(x);
+                        ProgramCounter::Next
+                    }
+                    None => self.invalid_address(opcode, x + 1),
                 }
-                ProgramCounter::Next
             }
-            (0x0f, _, 0x06, 0x05) => {
-                // LD (Vx, I): Liest alle Werte aus dem RAM mit Startpunkt im Index Register
-                // und kopiert diese in alle Register korrespondierend
-                for i in 0..REGISTER_SIZE {
-                    self.registers[i] = self.ram[self.i_reg as usize + i];
+            Instruction::LdVxI => {
+                // LD (Vx, I): Liest die Werte aus dem RAM mit Startpunkt im Index Register
+                // und kopiert diese in die Register V0 bis Vx
+                match self.i_range(x + 1) {
+                    Some(start) => {
+                        self.registers[..=x].copy_from_slice(&self.ram[start..=start + x]);
+                        self.memory_quirk(x);
+                        ProgramCounter::Next
+                    }
+                    None => self.invalid_address(opcode, x + 1),
                 }
-                ProgramCounter::Next
             }
-            _ => {
-                err!("The provided Opcode (`{:#X}`) is not supported or invalid", opcode);
+            // 0NNN ohne CDP1802, FX30 ohne große Schrift, CHIP-8X Instruktionen ohne CHIP-8X
+            Instruction::Sys
+            | Instruction::LdHf
+            | Instruction::AddNibbles
+            | Instruction::SkpSecond
+            | Instruction::SknpSecond
+            | Instruction::Pitch
+            | Instruction::Input
+            | Instruction::Invalid => {
+                error!(self.platform, target::CPU, "The provided Opcode (`{:#X}`) is not supported or invalid", opcode);
+                self.emit(Event::InvalidOpcode(opcode));
                 success = false;
                 ProgramCounter::Next
             }
         };
 
+        let was_waiting = self.waiting_for_key();
+        self.blocked = None;
         match pc_change {
             ProgramCounter::Next => self.pc += OPCODE_SIZE as u16,
             ProgramCounter::Skip => self.pc += 2 * OPCODE_SIZE as u16,
-            ProgramCounter::Block => self.pc -= OPCODE_SIZE as u16,
+            ProgramCounter::Block => self.blocked = Some(StopReason::WaitingForKey),
+            ProgramCounter::Wait => self.blocked = Some(StopReason::VBlank),
             ProgramCounter::Jump(addr) => self.pc = addr as u16,
+            ProgramCounter::Halt(reason) => self.halt(reason),
+        }
+        match (was_waiting, self.waiting_for_key()) {
+            (false, true) => self.emit(Event::KeyWaitStarted),
+            (true, false) => self.emit(Event::KeyWaitEnded),
+            _ => {}
+        }
+        if self.machine.vip_memory {
+            self.mirror_to_ram(matches!(instruction, Instruction::Cls | Instruction::Drw | Instruction::Sys | Instruction::Exit));
         }
 
         Output {
             success,
             opcode,
+            halt: self.halted,
         }
     }
 
+    // Ob das Programm weiterläuft, hält an, wenn der PC das Ende des RAMs erreicht hat
+    fn running(&mut self) -> bool {
+        if self.pc as usize + 1 >= self.ram.len() {
+            self.halt(HaltReason::EndOfMemory);
+        }
+        self.halted.is_none()
+    }
 
-    // Wird genutzt, um die Schrift in den RAM zu laden
-    fn initialize(&mut self) {
-        for i in 0..80 {
-            self.ram[i] = FONT[i];
+    // Beginnt die Sammlung der Ereignisse für ein `FrameResult`
+    pub(crate) fn begin_frame(&mut self) {
+        self.frame_events = EventSet::default();
+    }
+
+    // Fasst das Ergebnis eines Frames mit den seit `begin_frame` gemeldeten Ereignissen zusammen
+    pub(crate) fn end_frame(&self, result: RunResult) -> FrameResult {
+        let events = self.frame_events;
+        FrameResult {
+            cycles: result.cycles,
+            idle_cycles: result.idle_cycles,
+            stop: result.stop,
+            display_changed: result.display_changed,
+            sound_started: events.contains(EventKind::SoundStarted),
+            sound_stopped: events.contains(EventKind::SoundStopped),
+            sound_active: result.sound_active,
+            key_wait_started: events.contains(EventKind::KeyWaitStarted),
+            key_wait_ended: events.contains(EventKind::KeyWaitEnded),
+            waiting_for_key: result.waiting_for_key,
+            invalid_opcode: if result.stop == StopReason::InvalidOpcode { Some(result.opcode) } else { None },
+            halt: result.halt,
         }
     }
 
-    pub fn tick(&mut self) -> Output {
-        if self.registers[Register::DT as usize] > 0 {
-            self.registers[Register::DT as usize] -= 1;
+    // Meldet ein Ereignis an den Empfänger und merkt es sich für `run_frame`
+    fn emit(&mut self, event: Event) {
+        self.frame_events.insert(event.kind());
+        #[cfg(feature = "alloc")]
+        if let Some(handler) = self.events.as_mut() {
+            handler.on_event(event);
         }
+    }
 
-        if self.registers[Register::ST as usize] > 0 {
-            self.registers[Register::ST as usize] -= 1;
+    fn halt(&mut self, reason: HaltReason) {
+        if self.halted.is_none() {
+            info!(self.platform, target::CPU, "The program halted at {:#X}: {:?}", self.pc, reason);
+            self.halted = Some(reason);
+            self.emit(Event::Halted(reason));
         }
+    }
 
-        let opcode = self.fetch();
-        self.execute(opcode)
+    // Anfang des Bereichs `I..I + len`, falls er ganz im RAM liegt. Mit MegaChip (LDHI) und FX1E
+    // kann I über das Ende des RAMs hinaus zeigen
+    fn i_range(&self, len: usize) -> Option<usize> {
+        let start = self.i_reg as usize;
+        if start + len <= self.ram.len() {
+            Some(start)
+        } else {
+            None
+        }
     }
 
-    // Boolean, ob ein Piep-Ton gespielt werden soll
-    pub fn should_beep(&self) -> bool {
-        self.registers[Register::ST as usize] > 0
+    // Hält das Programm an, weil eine Instruktion über I außerhalb des RAMs zugreifen würde
+    fn invalid_address(&mut self, opcode: u16, len: usize) -> ProgramCounter {
+        error!(
+            self.platform, target::CPU,
+            "`{:#06X}` accesses {} bytes at {:#X}, outside of the RAM ({} bytes)",
+            opcode, len, self.i_reg, self.ram.len()
+        );
+        ProgramCounter::Halt(HaltReason::InvalidAddress)
     }
 
-    // Taste gedrückt
-    pub fn key_press(&mut self, key: Key) {
-        self.current_key = Some(key);
-        self.keys[key as usize] = true;
+    // Mit Logic-Quirk setzen OR, AND und XOR das VF-Register zurück
+    fn logic_quirk(&mut self) {
+        if self.quirks.logic {
+            self.registers[Register::VF as usize] = 0;
+        }
     }
 
-    // Taste losgelassen
-    pub fn key_up(&mut self, key: Key) {
-        if let Some(current_key) = self.current_key {
-            if key == current_key {
-                self.current_key = None;
+    // Schreibt Ergebnis und Flagge einer Rechnung, die Reihenfolge bestimmt
+    // bei Vx = VF der VF-Order-Quirk
+    fn write_carry(&mut self, x: usize, result: u8, flag: bool) {
+        self.registers[x] = result;
+        self.registers[Register::VF as usize] = flag as u8;
+        if self.quirks.vf_order {
+            self.registers[x] = result;
+        }
+    }
+
+    // Führt eine Unterroutine in Maschinensprache mit den Registerkonventionen des VIP aus
+    fn call_machine_code(&mut self, address: usize, x: usize, y: usize) -> ProgramCounter {
+        let cpu = &mut self.cpu;
+        cpu.r[2] = (VIP_STACK_END - 1 - 2 * usize::from(self.sp)) as u16;
+        cpu.r[3] = address as u16;
+        cpu.r[5] = self.pc + OPCODE_SIZE as u16;
+        cpu.r[6] = (VIP_REGISTERS + x) as u16;
+        cpu.r[7] = (VIP_REGISTERS + y) as u16;
+        cpu.r[0xA] = self.i_reg as u16;
+        cpu.r[0xB] = VIP_DISPLAY as u16;
+        cpu.p = 3;
+        cpu.x = 2;
+        let result = cpu.call(&mut self.ram, MACHINE_CODE_LIMIT);
+        trace!(self.platform, target::CPU, "Machine code at {:#05X} ran {} instructions", address, result.cycles);
+        if let Some(written) = result.written {
+            self.invalidate(written.start, written.end);
+        }
+        self.i_reg = u32::from(self.cpu.r[0xA]);
+        if result.returned {
+            ProgramCounter::Jump(usize::from(self.cpu.r[5]))
+        } else {
+            error!(self.platform, target::CPU, "The machine code at {:#05X} did not return", address);
+            ProgramCounter::Halt(HaltReason::MachineCode)
+        }
+    }
+
+    // Bestimmt den Befehlssatz der ROM und stellt den Processor darauf ein, sofern sie nicht in
+    // der Datenbank steht. Von Hand gewählte Variante und Quirks bleiben dabei erhalten
+    #[cfg(feature = "alloc")]
+    fn detect(&mut self, data: &[u8]) {
+        let analysis = RomAnalysis::analyze(data, self.machine.load_address);
+        debug!(
+            self.platform, target::LOADER,
+            "The ROM uses {:?} ({:.0}% of {} reachable instructions)",
+            analysis.instruction_set, analysis.confidence * 100.0, analysis.reachable
+        );
+        self.rom_analysis = Some(analysis);
+        #[cfg(feature = "database")]
+        if self.rom_info.is_some() {
+            return;
+        }
+        if !self.auto_detect || analysis.confidence < MIN_CONFIDENCE {
+            return;
+        }
+        if !self.configure(analysis.instruction_set, analysis.machine_code) {
+            return;
+        }
+        if let (Some(quirks), false) = (analysis.instruction_set.quirks(), self.quirks_chosen) {
+            self.replaced.quirks.get_or_insert(self.quirks);
+            self.quirks = quirks;
+        }
+    }
+
+    // Stellt Variante, Maschinensprache und Schrift auf den Befehlssatz einer ROM ein. Eine mit
+    // `set_variant` gewählte Variante bleibt erhalten. Gibt `false` zurück, wenn der Befehlssatz
+    // nicht unterstützt wird und die ROM als CHIP-8 läuft
+    #[cfg(feature = "alloc")]
+    fn configure(&mut self, instruction_set: InstructionSet, machine_code: bool) -> bool {
+        if matches!(instruction_set, InstructionSet::Schip | InstructionSet::XoChip) {
+            warn!(
+                self.platform, target::LOADER,
+                "The ROM uses {:?}, which is not supported. It runs as CHIP-8 and its extensions are invalid",
+                instruction_set
+            );
+            return false;
+        }
+        // Den RAM nachträglich zu vergrößern würde die bereits geladene ROM und den Speicheraufbau ändern
+        let variant = instruction_set.variant();
+        if let Err(error) = self.machine.check_variant(variant) {
+            warn!(
+                self.platform, target::LOADER,
+                "The ROM uses {:?}, but runs as CHIP-8: {} (use `MachineConfig::megachip()`)",
+                instruction_set, error
+            );
+            return false;
+        }
+        if instruction_set != InstructionSet::Chip8 || machine_code {
+            info!(self.platform, target::LOADER, "Configuring for {:?}", instruction_set);
+        }
+        // Die Hires-Erkennung in `load` kann die Variante bereits ersetzt haben. CHIP-8 setzt sie
+        // nicht zurück, da die Datenbank Hires-ROMs als "originalChip8" führt
+        if !self.variant_chosen && instruction_set != InstructionSet::Chip8 && self.variant != variant {
+            self.replaced.variant.get_or_insert(self.variant);
+            self.variant = variant;
+        }
+        if machine_code && !self.machine.machine_code {
+            self.replaced.machine_code = Some(false);
+            self.machine.machine_code = true;
+        }
+        // MegaChip baut auf SCHIP auf und braucht die große Schrift für FX30
+        if instruction_set == InstructionSet::MegaChip && !self.font.has_big() {
+            let font = self.font;
+            match self.set_font(Font::from_set(FontSet::Schip)) {
+                Ok(()) => self.replaced.font = Some(font),
+                Err(error) => warn!(self.platform, target::LOADER, "Could not switch to the SCHIP font: {}", error),
             }
         }
-        self.keys[key as usize] = false;
+        true
     }
 
-    pub fn reset(&mut self) {
-        // Speicher leeren
-        for i in 0..RAM_SIZE {
-            self.ram[i] = 0;
+    // Macht die Einstellungen rückgängig, die beim Laden der vorherigen ROM ersetzt wurden.
+    // Seitdem von Hand gewählte Einstellungen haben ihren Eintrag bereits verworfen
+    #[cfg(feature = "alloc")]
+    fn restore_replaced(&mut self) {
+        let replaced = core::mem::take(&mut self.replaced);
+        if let Some(variant) = replaced.variant {
+            self.variant = variant;
+        }
+        if let Some(quirks) = replaced.quirks {
+            self.quirks = quirks;
+        }
+        if let Some(machine_code) = replaced.machine_code {
+            self.machine.machine_code = machine_code;
+        }
+        // Wird von `reset` wieder in den Speicher geschrieben
+        if let Some(font) = replaced.font {
+            self.font = font;
+        }
+        if let Some(tick_rate) = replaced.tick_rate {
+            self.tick_rate = tick_rate;
         }
+        if let Some(colors) = replaced.colors {
+            self.colors = colors;
+        }
+    }
 
-        // Stack leeren
-        for i in 0..STACK_SIZE {
-            self.stack[i] = 0;
+    // MegaChip: Befehle zwischen 0010 und 09NN, siehe `is_megachip_opcode`
+    #[cfg(feature = "alloc")]
+    fn execute_megachip(&mut self, opcode: u16, kk: u8) -> ProgramCounter {
+        let start = (self.i_reg as usize).min(self.ram.len());
+        let megachip = match self.megachip.as_mut() {
+            Some(megachip) => megachip,
+            None => return ProgramCounter::Next,
+        };
+        self.effects = self.effects.wrapping_add(1);
+        match opcode >> 8 {
+            0x00 => match opcode & 0xF0 {
+                0xB0 => megachip.scroll_vertical(-((opcode & 0x0F) as isize)),
+                0xC0 => megachip.scroll_vertical((opcode & 0x0F) as isize),
+                _ => megachip.scroll_horizontal(if opcode == 0x00FB { 4 } else { -4 }),
+            },
+            0x01 => {
+                // LDHI: Die unteren 16 Bit von I stehen im nächsten Wort
+                let next = usize::from(self.pc) + OPCODE_SIZE;
+                return match self.ram.get(next..next + 2) {
+                    Some(&[high, low]) => {
+                        self.i_reg = u32::from(kk) << 16 | u32::from(high) << 8 | u32::from(low);
+                        ProgramCounter::Skip
+                    }
+                    _ => ProgramCounter::Halt(HaltReason::EndOfMemory),
+                };
+            }
+            0x02 => megachip.load_palette(&self.ram[start..(start + 4 * usize::from(kk)).min(self.ram.len())]),
+            // 0 steht jeweils für 256 Pixel
+            0x03 => megachip.sprite_width = if kk == 0 { 256 } else { usize::from(kk) },
+            0x04 => megachip.sprite_height = if kk == 0 { 256 } else { usize::from(kk) },
+            0x05 => megachip.alpha = kk,
+            0x06 => {
+                // 0600 spielt den Ton in einer Schleife, 0601 nur einmal
+                megachip.sound = DigitizedSound::parse(&self.ram[start..], kk & 0x0F == 0);
+                if megachip.sound.is_none() {
+                    error!(self.platform, target::CPU, "No digitized sound at {:#X}", start);
+                }
+            }
+            0x07 => megachip.sound = None,
+            0x08 => megachip.blend = BlendMode::from_nibble(kk & 0x0F).unwrap_or(BlendMode::Normal),
+            _ => megachip.collision_color = kk,
         }
+        ProgramCounter::Next
+    }
 
-        // Register leeren
-        for i in 0..REGISTER_SIZE {
-            self.registers[i] = 0;
+    // DRW (Vx, Vy, n): Liest n-Bytes aus dem RAM mit dem Startpunkt im
+    // Index-Register. DIe Bytes werden dann als "Sprite" auf dem Bildschirm
+    // an der Stelle (Vx | Vy) dargestellt. Wenn an der Stelle ein Pixel
+    // gelöscht wird, wird das VF Register auf 1 gestellt, ansonsten 0
+    // Ohne Wrap-Quirk werden Sprites am Rand abgeschnitten
+    fn draw(&mut self, opcode: u16, x: usize, y: usize, n: usize) -> ProgramCounter {
+        let origin_x = self.registers[x] as usize % DISPLAY_WIDTH;
+        let height = self.display.height();
+        let origin_y = self.registers[y] as usize % height;
+        let rows = if self.quirks.wrap { n } else { n.min(height - origin_y) };
+        let start = match self.i_range(rows) {
+            Some(start) => start,
+            None => return self.invalid_address(opcode, rows),
+        };
+        self.registers[Register::VF as usize] = 0;
+        self.vblank = false;
+        self.effects = self.effects.wrapping_add(1);
+        for byte in 0..rows {
+            let sprite = self.ram[start + byte];
+            if self.display.draw_row((origin_y + byte) % height, origin_x, sprite, self.quirks.wrap) {
+                self.registers[Register::VF as usize] = 1;
+            }
         }
-        self.sp = 0;
-        self.pc = 0x200;
+        trace!(
+            self.platform, target::DISPLAY,
+            "Drew {} rows from {:#X} at ({}, {}), collision: {}",
+            n, self.i_reg, origin_x, origin_y, self.registers[Register::VF as usize]
+        );
 
-        // Display leeren
-        self.display.cls();
+        ProgramCounter::Next
+    }
 
-        // Schrift neu hinzufügen
-        self.initialize();
+    // MegaChip: DXYN zeichnet einen Sprite aus Farbindizes, N wird ignoriert
+    #[cfg(feature = "alloc")]
+    fn draw_megachip(&mut self, x: usize, y: usize) -> ProgramCounter {
+        if let Some(megachip) = self.megachip.as_mut() {
+            let start = (self.i_reg as usize).min(self.ram.len());
+            let end = (start + megachip.sprite_size()).min(self.ram.len());
+            let (x, y) = (usize::from(self.registers[x]), usize::from(self.registers[y]));
+            let collision = megachip.draw_sprite(x, y, &self.ram[start..end]);
+            self.registers[Register::VF as usize] = u8::from(collision);
+            self.effects = self.effects.wrapping_add(1);
+        }
+        ProgramCounter::Next
     }
 
-    // Laden von Daten in den CPU
-    pub fn load(&mut self, data: Vec<u8>) -> usize {
-        self.reset();
-        let mut start = 0;
-        self.pc = 0x200;
-        for byte in data.iter() {
-            self.ram[self.pc as usize + start] = *byte;
-            start += 1;
+    // Verschiebt das Index-Register nach FX55 / FX65 je nach Quirk
+    fn memory_quirk(&mut self, x: usize) {
+        if self.quirks.memory_leave_i_unchanged {
+            return;
         }
-        log!("Tomo: Data was successfully loaded into the ram with a size of {} bytes.", start);
-        start
+        let offset = if self.quirks.memory_increment_by_x { x } else { x + 1 };
+        self.i_reg += offset as u32;
     }
 
-    pub fn test_set_registers(&mut self, idx: usize, data: u8) {
-        self.registers[idx] = data;
+    // Meldet der Plattform, wenn der Piep-Ton an- bzw. ausgeht
+    fn update_audio(&mut self) {
+        let beeping = self.should_beep();
+        if beeping != self.beeping {
+            self.beeping = beeping;
+            self.platform.audio(beeping);
+            self.emit(if beeping { Event::SoundStarted } else { Event::SoundStopped });
+        }
     }
+}
 
-    pub fn test_get_registers(&mut self, idx: usize) -> u8 {
-        self.registers[idx]
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+impl Processor {
+    // `run_until` für JavaScript: `condition` erhält nach jeder Instruktion den PC,
+    // ein wahres Ergebnis (oder eine Exception) hält an
+    #[wasm_bindgen(js_name = run_until)]
+    pub fn run_until_js(&mut self, cycles: u32, condition: &js_sys::Function) -> RunResult {
+        self.run_until(cycles, |processor| {
+            condition
+                .call1(&JsValue::NULL, &JsValue::from(processor.pc))
+                .map_or(true, |stop| stop.is_truthy())
+        })
     }
+}
 
-    pub fn test_set_ram(&mut self, idx: usize, data: u8) {
-        self.ram[idx] = data;
+#[cfg(all(target_arch = "wasm32", feature = "alloc"))]
+#[wasm_bindgen]
+impl Processor {
+    // Ruft `callback(kind: EventKind, value: number)` bei jedem Ereignis auf. Während des
+    // Aufrufs darf der Processor nicht benutzt werden, da er gerade ausgeführt wird
+    pub fn set_event_callback(&mut self, callback: js_sys::Function) {
+        self.set_event_handler(Box::new(crate::chip8::events::JsEventHandler::new(callback)));
     }
 
-    pub fn test_get_ram(&mut self, idx: usize) -> u8 {
-        self.ram[idx]
+    pub fn clear_event_callback(&mut self) {
+        self.clear_event_handler();
     }
+}
 
-    pub fn test_get_stack(&mut self, idx: usize) -> u16 {
-        self.stack[idx]
+#[cfg(feature = "database")]
+#[wasm_bindgen]
+impl Processor {
+    // Informationen zur zuletzt geladenen ROM aus der Datenbank
+    pub fn rom_info(&self) -> Option<RomInfo> {
+        self.rom_info.clone()
     }
 
-    pub fn test_set_stack(&mut self, idx: usize, data: u16) {
-        self.stack[idx] = data;
+    // Ersetzt die eingebettete Datenbank durch neuere Dateien der chip-8-database
+    pub fn set_database_json(&mut self, programs: &str, hashes: &str, platforms: &str) -> Result<(), crate::database::DatabaseError> {
+        self.set_database(RomDatabase::from_json(programs, hashes, platforms)?);
+        Ok(())
+    }
+
+    // Übernimmt Plattform, Quirks (sofern nicht mit `set_quirks` gewählt), Geschwindigkeit und
+    // Farben einer erkannten ROM. Beim nächsten `load` gelten wieder die vorherigen Einstellungen
+    pub fn apply_rom_info(&mut self, info: &RomInfo) {
+        if let Some(instruction_set) = info.instruction_set() {
+            self.configure(instruction_set, info.machine_code());
+        }
+        if !self.quirks_chosen {
+            self.replaced.quirks.get_or_insert(self.quirks);
+            self.quirks = info.quirks;
+        }
+        self.replaced.tick_rate.get_or_insert(self.tick_rate);
+        self.tick_rate = info.tick_rate;
+        if let Some(colors) = info.colors {
+            self.replaced.colors.get_or_insert(self.colors);
+            self.colors = colors;
+        }
+    }
+}
+
+#[cfg(feature = "database")]
+impl Processor {
+    pub fn set_database(&mut self, database: RomDatabase) {
+        self.database = Some(std::rc::Rc::new(database));
+    }
+
+    // Sucht die ROM in der Datenbank und übernimmt ggf. ihre Einstellungen
+    fn identify(&mut self, data: &[u8]) {
+        let database = match &self.database {
+            Some(database) => database.as_ref(),
+            None => RomDatabase::embedded(),
+        };
+        let info = database.identify(data);
+        match &info {
+            Some(info) => {
+                info!(self.platform, target::LOADER, "Identified `{}` ({}) with SHA-1 {}", info.title, info.platform_name, info.sha1);
+                if self.auto_configure {
+                    self.apply_rom_info(info);
+                }
+            }
+            None => debug!(self.platform, target::LOADER, "ROM is not in the database"),
+        }
+        self.rom_info = info;
+    }
+}
+
+#[cfg(feature = "octo")]
+#[wasm_bindgen]
+impl Processor {
+    // Lädt eine Octo-Cartridge (GIF) und übernimmt ihre Optionen
+    pub fn load_cartridge(&mut self, gif: &[u8]) -> Result<usize, CartridgeError> {
+        let cartridge = Cartridge::decode(gif)?;
+        let rom = cartridge.rom()?;
+        cartridge.options.validate()?;
+        let size = self.load(&rom)?;
+        self.apply_octo_options(&cartridge.options)?;
+        info!(self.platform, target::LOADER, "Applied the options of the Octo cartridge");
+        Ok(size)
+    }
+
+    // Aktuelle Einstellungen im Format von Octo
+    pub fn octo_options(&self) -> OctoOptions {
+        let mut options = OctoOptions::new();
+        options.tickrate = self.tick_rate;
+        options.screen_rotation = self.screen_rotation;
+        options.set_quirks(&self.quirks);
+        options.set_colors(&self.colors);
+        options
+    }
+
+    // Übernimmt Geschwindigkeit, Farben, Quirks und Drehung aus Octo-Optionen. Optionen, die
+    // Tomo nicht umsetzen kann (siehe `OctoOptions::validate`), werden abgelehnt, ohne etwas zu
+    // übernehmen. Eine andere Schriftart als die von Octo wird nur gemeldet
+    pub fn apply_octo_options(&mut self, options: &OctoOptions) -> Result<(), OptionsError> {
+        options.validate()?;
+        if options.font_style != OctoOptions::new().font_style {
+            let style = &options.font_style;
+            warn!(self.platform, target::LOADER, "The font style {} is not supported, the current font is kept", style);
+        }
+        self.tick_rate = options.tickrate;
+        self.screen_rotation = options.screen_rotation;
+        self.quirks = options.quirks();
+        self.colors = options.colors();
+        Ok(())
+    }
+}
+
+impl Default for Processor {
+    fn default() -> Self {
+        Self::new()
     }
 }
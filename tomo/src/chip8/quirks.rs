@@ -0,0 +1,87 @@
+use crate::prelude::*;
+
+// Verhaltensunterschiede der verschiedenen CHIP-8 Interpreter, benannt
+// wie in der Community-Datenbank (chip-8-database)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quirks {
+    // 8XY6 / 8XYE verschieben Vx statt Vy
+    pub shift: bool,
+    // FX55 / FX65 erhöhen das Index-Register nur um X statt X + 1
+    pub memory_increment_by_x: bool,
+    // FX55 / FX65 lassen das Index-Register unverändert
+    pub memory_leave_i_unchanged: bool,
+    // Sprites werden am Rand umgebrochen statt abgeschnitten
+    pub wrap: bool,
+    // BNNN springt zu NNN + Vx statt NNN + V0
+    pub jump: bool,
+    // DXYN wartet auf den nächsten Frame (vertical blank)
+    pub vblank: bool,
+    // 8XY1 / 8XY2 / 8XY3 setzen VF auf 0
+    pub logic: bool,
+    // Arithmetik schreibt das Ergebnis nach VF, falls Vx selbst VF ist
+    // (ansonsten überschreibt die Flagge das Ergebnis)
+    pub vf_order: bool,
+}
+
+#[wasm_bindgen]
+impl Quirks {
+    // Bisheriges Verhalten von Tomo. Nur 8XY4 mit X = F ändert sich: Wie bei den anderen
+    // Rechnungen bleibt in VF das Ergebnis statt der Flagge
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Quirks {
+            shift: true,
+            memory_increment_by_x: false,
+            memory_leave_i_unchanged: true,
+            wrap: true,
+            jump: false,
+            vblank: false,
+            logic: false,
+            vf_order: true,
+        }
+    }
+
+    // Original-Interpreter des COSMAC VIP
+    pub fn cosmac_vip() -> Self {
+        Quirks {
+            shift: false,
+            memory_increment_by_x: false,
+            memory_leave_i_unchanged: false,
+            wrap: false,
+            jump: false,
+            vblank: true,
+            logic: true,
+            vf_order: false,
+        }
+    }
+
+    // Wie CHIP-8 heutzutage meistens umgesetzt wird
+    pub fn modern() -> Self {
+        Quirks {
+            vblank: false,
+            logic: false,
+            ..Self::cosmac_vip()
+        }
+    }
+
+    // SUPER-CHIP 1.1 für den HP48
+    pub fn superchip() -> Self {
+        Quirks {
+            shift: true,
+            memory_increment_by_x: false,
+            memory_leave_i_unchanged: true,
+            wrap: false,
+            jump: true,
+            vblank: false,
+            logic: false,
+            vf_order: false,
+        }
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,305 @@
+//! Aufnahme des Displays als animiertes GIF oder Y4M-Video, der Ton optional als WAV
+//!
+//! `capture` wird einmal pro emuliertem Frame (60 Hz) aufgerufen und merkt sich das
+//! Display sowie, ob der Sound-Timer läuft. Die Kodierer liefern fertige Dateien als Bytes.
+
+use alloc::vec::Vec;
+
+use super::processor::Processor;
+use super::{Colors, Display, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use crate::prelude::*;
+
+// Bilder pro Sekunde der Aufnahme
+pub const FRAME_RATE: u32 = 60;
+// Standardlänge einer Aufnahme: eine Minute
+pub const DEFAULT_LIMIT: u32 = 60 * FRAME_RATE;
+
+// Größte Code-Länge des LZW-Verfahrens in GIFs
+const MAX_CODE_SIZE: u32 = 12;
+
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Recorder {
+    frames: Vec<Display>,
+    sound: Vec<bool>,
+    pub colors: Colors,
+    // Vergrößerungsfaktor der Pixel (1 bis 32)
+    scale: u32,
+    // Höchstzahl an Frames, danach wird nichts mehr aufgenommen
+    pub limit: u32,
+}
+
+#[wasm_bindgen]
+impl Recorder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(colors: Colors, scale: u32) -> Self {
+        Recorder {
+            frames: Vec::new(),
+            sound: Vec::new(),
+            colors,
+            scale: scale.clamp(1, super::screenshot::MAX_SCALE),
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    // Nimmt den aktuellen Frame des Processors auf. Gibt `false` zurück, wenn das Limit erreicht ist
+    pub fn capture(&mut self, processor: &Processor) -> bool {
+        self.capture_display(&processor.display, processor.should_beep())
+    }
+
+    pub fn capture_display(&mut self, display: &Display, sound: bool) -> bool {
+        if self.frames.len() >= self.limit as usize {
+            return false;
+        }
+        self.frames.push(*display);
+        self.sound.push(sound);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.sound.clear();
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    // Dauer der Aufnahme in Millisekunden
+    pub fn duration(&self) -> u32 {
+        self.frames.len() as u32 * 1000 / FRAME_RATE
+    }
+
+    // Animiertes GIF, das endlos wiederholt wird. GIFs kennen nur Verzögerungen in
+    // Hundertstelsekunden, daher werden gleiche Frames zusammengefasst und Frames,
+    // die kürzer als 2/100 s sichtbar wären, übersprungen
+    pub fn to_gif(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        // Globale Farbtabelle mit 2 Farben
+        gif.extend_from_slice(&[0x80, 0, 0]);
+        for color in [self.colors.background, self.colors.foreground] {
+            gif.extend_from_slice(&color.to_be_bytes()[1..]);
+        }
+        // Endlos wiederholen (NETSCAPE2.0)
+        gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        gif.extend_from_slice(b"NETSCAPE2.0");
+        gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        let rows = self.height();
+        let keyframes = self.keyframes();
+        for (index, (frame, start)) in keyframes.iter().enumerate() {
+            let end = keyframes.get(index + 1).map_or(self.frames.len(), |(_, next)| *next);
+            let delay = centiseconds(end) - centiseconds(*start);
+
+            // Graphic Control Extension mit der Verzögerung
+            gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+            gif.extend_from_slice(&(delay as u16).to_le_bytes());
+            gif.extend_from_slice(&[0x00, 0x00]);
+
+            // Image Descriptor über das ganze Bild, ohne lokale Farbtabelle
+            gif.extend_from_slice(&[0x2C, 0, 0, 0, 0]);
+            gif.extend_from_slice(&(width as u16).to_le_bytes());
+            gif.extend_from_slice(&(height as u16).to_le_bytes());
+            gif.push(0x00);
+
+            let data = lzw_encode(&self.indices(frame, rows));
+            gif.push(MIN_CODE_SIZE as u8);
+            for block in data.chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend_from_slice(block);
+            }
+            gif.push(0);
+        }
+        gif.push(0x3B);
+        gif
+    }
+
+    // Unkomprimiertes Video im YUV4MPEG2-Format (4:4:4, 60 Bilder pro Sekunde)
+    pub fn to_y4m(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let mut y4m = f!("YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444\n", width, height, FRAME_RATE).into_bytes();
+        let rows = self.height();
+        let (background, foreground) = (yuv(self.colors.background), yuv(self.colors.foreground));
+        // Je Ebene (Y, Cb, Cr) der Wert für beide Farbindizes
+        let planes = [0, 1, 2].map(|plane| [background[plane], foreground[plane]]);
+        for frame in &self.frames {
+            y4m.extend_from_slice(b"FRAME\n");
+            let indices = self.indices(frame, rows);
+            for plane in &planes {
+                y4m.extend(indices.iter().map(|index| plane[*index as usize]));
+            }
+        }
+        y4m
+    }
+
+    // Tonspur als WAV (8 Bit, mono): Ein Rechteckton mit `frequency` Hz, solange der Sound-Timer läuft
+    pub fn to_wav(&self, sample_rate: u32, frequency: u32) -> Vec<u8> {
+        let sample_rate = sample_rate.max(1);
+        let mut samples = Vec::with_capacity((self.frames.len() as u64 * u64::from(sample_rate) / u64::from(FRAME_RATE)) as usize);
+        for (index, sound) in self.sound.iter().enumerate() {
+            // Auf ganze Samples gerundet, ohne dass sich Fehler aufsummieren
+            let start = index as u64 * u64::from(sample_rate) / u64::from(FRAME_RATE);
+            let end = (index as u64 + 1) * u64::from(sample_rate) / u64::from(FRAME_RATE);
+            for sample in start..end {
+                let high = sample * 2 * u64::from(frequency) / u64::from(sample_rate) % 2 == 0;
+                samples.push(match (sound, high) {
+                    (false, _) => 0x80,
+                    (true, true) => 0xC0,
+                    (true, false) => 0x40,
+                });
+            }
+        }
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        // PCM, 1 Kanal, Abtastrate, Bytes pro Sekunde, Bytes pro Sample, Bits pro Sample
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&8u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&samples);
+        wav
+    }
+}
+
+impl Recorder {
+    // Größe des GIFs, bei wechselnder Höhe des Displays die des höchsten Frames
+    fn size(&self) -> (u32, u32) {
+        (DISPLAY_WIDTH as u32 * self.scale, self.height() as u32 * self.scale)
+    }
+
+    fn height(&self) -> usize {
+        self.frames.iter().map(Display::height).max().unwrap_or(DISPLAY_HEIGHT)
+    }
+
+    // Farbindex (0 oder 1) jedes Pixels der ersten `height` Zeilen, Zeile für Zeile und vergrößert
+    fn indices(&self, display: &Display, height: usize) -> Vec<u8> {
+        let scale = self.scale as usize;
+        let mut indices = Vec::with_capacity(DISPLAY_WIDTH * height * scale * scale);
+        // Niedrigere Frames werden unten mit inaktiven Pixeln aufgefüllt
+        for y in 0..height * scale {
+            indices.extend((0..DISPLAY_WIDTH * scale).map(|x| display.get_pixel(y / scale, x / scale) as u8));
+        }
+        indices
+    }
+
+    // Frames, die sich vom vorherigen unterscheiden, mit dem Index ihres ersten Auftretens
+    fn keyframes(&self) -> Vec<(Display, usize)> {
+        let mut keyframes: Vec<(Display, usize)> = Vec::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            match keyframes.last_mut() {
+                Some((last, _)) if same(last, frame) => {}
+                // Zu kurz für ein GIF: Der neue Inhalt ersetzt den vorherigen
+                Some((last, start)) if centiseconds(index) - centiseconds(*start) < 2 => *last = *frame,
+                _ => keyframes.push((*frame, index)),
+            }
+        }
+        keyframes
+    }
+}
+
+fn same(a: &Display, b: &Display) -> bool {
+    a.height() == b.height() && (0..a.height()).all(|y| a.row(y) == b.row(y))
+}
+
+// Zeitpunkt eines Frames in Hundertstelsekunden
+fn centiseconds(frame: usize) -> u32 {
+    (frame as u32 * 100 + FRAME_RATE / 2) / FRAME_RATE
+}
+
+// Farbe 0xRRGGBB als Y, Cb und Cr nach BT.601 (begrenzter Wertebereich)
+fn yuv(color: u32) -> [u8; 3] {
+    let [_, r, g, b] = color.to_be_bytes();
+    let (r, g, b) = (i32::from(r), i32::from(g), i32::from(b));
+    [
+        (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8,
+        (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8,
+        (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8,
+    ]
+}
+
+// GIF verlangt mindestens 2 Bit, auch wenn nur 2 Farben genutzt werden
+const MIN_CODE_SIZE: u32 = 2;
+
+// LZW-Kompression für GIF, Codes mit variabler Länge und niederwertigste Bits zuerst
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+    // Für jeden Code die Folgecodes je Farbindex (0 = noch nicht vergeben)
+    let mut children: Vec<[u16; 4]> = alloc::vec![[0; 4]; 1 << MAX_CODE_SIZE];
+    let mut next = end + 1;
+    let mut size = MIN_CODE_SIZE + 1;
+
+    let mut output = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+    let mut write = |code: u16, size: u32| {
+        buffer |= u32::from(code) << bits;
+        bits += size;
+        while bits >= 8 {
+            output.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+
+    write(clear, size);
+    let mut pixels = indices.iter();
+    let mut prefix = match pixels.next() {
+        Some(pixel) => u16::from(*pixel),
+        None => clear,
+    };
+    for pixel in pixels {
+        let pixel = *pixel as usize;
+        let child = children[prefix as usize][pixel];
+        if child != 0 {
+            prefix = child;
+            continue;
+        }
+        write(prefix, size);
+        if next < 1 << MAX_CODE_SIZE {
+            children[prefix as usize][pixel] = next;
+            // Der Decoder ist einen Eintrag zurück und vergrößert die Codes erst danach
+            if u32::from(next) == 1 << size && size < MAX_CODE_SIZE {
+                size += 1;
+            }
+            next += 1;
+        } else {
+            // Tabelle voll: Neu beginnen
+            write(clear, size);
+            children.iter_mut().for_each(|entry| *entry = [0; 4]);
+            next = end + 1;
+            size = MIN_CODE_SIZE + 1;
+        }
+        prefix = pixel as u16;
+    }
+    if !indices.is_empty() {
+        write(prefix, size);
+        // Der Decoder legt auch für den letzten Code einen Eintrag an
+        if u32::from(next) == 1 << size && size < MAX_CODE_SIZE {
+            size += 1;
+        }
+    }
+    write(end, size);
+    if bits > 0 {
+        output.push(buffer as u8);
+    }
+    output
+}
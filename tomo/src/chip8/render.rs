@@ -0,0 +1,324 @@
+//! Darstellung des Displays als RGBA-Bild, optional mit Filtern für Pixel-Art
+//!
+//! Die Filter arbeiten auf den Farben der Pixel und vergrößern das Bild um einen festen
+//! Faktor (`filter_scale`). Das Ergebnis kann direkt in ein `ImageData` kopiert werden.
+//! Bei CHIP-8X kommen die Farben aus der Farbschicht statt aus `Colors`, im MegaChip-Modus
+//! wird dessen Frame mit 256x192 Pixeln dargestellt.
+
+use alloc::vec::Vec;
+
+use super::color::ColorLayer;
+use super::machine::Variant;
+use super::megachip::{MEGACHIP_HEIGHT, MEGACHIP_WIDTH};
+use super::processor::Processor;
+use super::{Colors, Display, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use crate::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    // Ein Pixel pro Pixel
+    None,
+    // AdvMAME2x bzw. AdvMAME3x: Kanten werden anhand der direkten Nachbarn geglättet
+    Scale2x,
+    Scale3x,
+    // Eric Johnstons EPX (2x), lässt Pixel mit drei gleichen Nachbarn unverändert
+    Epx,
+    // Kantenerkennung nach xBR (4x): Diagonale Kanten werden mit Zwischenfarben geglättet
+    Xbr,
+    // Röhrenbildschirm (4x) mit dunklen Zeilen zwischen den Pixeln und RGB-Streifenmaske
+    Crt,
+}
+
+// Vergrößerungsfaktor eines Filters
+#[wasm_bindgen]
+pub fn filter_scale(filter: Filter) -> usize {
+    match filter {
+        Filter::None => 1,
+        Filter::Scale2x | Filter::Epx => 2,
+        Filter::Scale3x => 3,
+        Filter::Xbr | Filter::Crt => 4,
+    }
+}
+
+#[wasm_bindgen]
+impl Display {
+    // Bild in Originalgröße, 4 Bytes (RGBA) pro Pixel
+    pub fn to_rgba(&self, colors: &Colors) -> Vec<u8> {
+        self.render(colors, Filter::None)
+    }
+
+    // Bild mit Filter, `filter_scale(filter)` mal so breit und hoch (64 x `height()` Pixel im Original)
+    pub fn render(&self, colors: &Colors, filter: Filter) -> Vec<u8> {
+        let scale = filter_scale(filter);
+        let mut output = alloc::vec![0; DISPLAY_WIDTH * self.height() * scale * scale * 4];
+        self.render_into(colors, filter, &mut output);
+        output
+    }
+
+    // Wie `render`, schreibt aber in einen vorhandenen Puffer (z.B. einmal pro Frame)
+    pub fn render_into(&self, colors: &Colors, filter: Filter, output: &mut [u8]) {
+        render_source(&Source::display(self, Palette::Mono(colors)), filter, output);
+    }
+
+    // Wie `render`, aber mit den Farben einer CHIP-8X Farbschicht
+    pub fn render_layer(&self, layer: &ColorLayer, filter: Filter) -> Vec<u8> {
+        let scale = filter_scale(filter);
+        let mut output = alloc::vec![0; DISPLAY_WIDTH * self.height() * scale * scale * 4];
+        render_source(&Source::display(self, Palette::Layer(layer)), filter, &mut output);
+        output
+    }
+}
+
+#[wasm_bindgen]
+impl Processor {
+    // Bild des Displays mit den Farben der gewählten Variante (`colors` bzw. die Farbschicht bei
+    // CHIP-8X), im MegaChip-Modus dessen Frame. Die Größe ohne Filter liefern `render_width`
+    // und `render_height`
+    pub fn render(&self, filter: Filter) -> Vec<u8> {
+        if let Some(megachip) = self.megachip() {
+            let source = Source { width: MEGACHIP_WIDTH, height: MEGACHIP_HEIGHT, pixels: Pixels::Rgb(megachip.frame()) };
+            let scale = filter_scale(filter);
+            let mut output = alloc::vec![0; MEGACHIP_WIDTH * MEGACHIP_HEIGHT * scale * scale * 4];
+            render_source(&source, filter, &mut output);
+            return output;
+        }
+        match self.variant {
+            Variant::Chip8x => self.display.render_layer(&self.color_layer(), filter),
+            _ => self.display.render(&self.colors, filter),
+        }
+    }
+
+    pub fn render_width(&self) -> usize {
+        if self.megachip().is_some() { MEGACHIP_WIDTH } else { DISPLAY_WIDTH }
+    }
+
+    pub fn render_height(&self) -> usize {
+        if self.megachip().is_some() { MEGACHIP_HEIGHT } else { self.display.height() }
+    }
+}
+
+fn render_source(source: &Source, filter: Filter, output: &mut [u8]) {
+    let scale = filter_scale(filter);
+    assert_eq!(output.len(), source.width * source.height * scale * scale * 4, "Wrong size of the output buffer");
+    let mut target = Target { output, width: source.width * scale, scale };
+    for y in 0..source.height as isize {
+        for x in 0..source.width as isize {
+            match filter {
+                Filter::None => target.fill(x, y, source.get(x, y)),
+                Filter::Scale2x => scale2x(source, &mut target, x, y),
+                Filter::Scale3x => scale3x(source, &mut target, x, y),
+                Filter::Epx => epx(source, &mut target, x, y),
+                Filter::Xbr => xbr(source, &mut target, x, y),
+                Filter::Crt => crt(source, &mut target, x, y),
+            }
+        }
+    }
+}
+
+enum Palette<'a> {
+    Mono(&'a Colors),
+    Layer(&'a ColorLayer),
+}
+
+enum Pixels<'a> {
+    Display(&'a Display, Palette<'a>),
+    // Fertige Farben (0xRRGGBB), zeilenweise
+    Rgb(&'a [u32]),
+}
+
+// Liest Farben, Pixel außerhalb des Bildes erhalten die Farbe des nächsten Randpixels
+struct Source<'a> {
+    width: usize,
+    height: usize,
+    pixels: Pixels<'a>,
+}
+
+impl<'a> Source<'a> {
+    fn display(display: &'a Display, palette: Palette<'a>) -> Self {
+        Source { width: DISPLAY_WIDTH, height: display.height(), pixels: Pixels::Display(display, palette) }
+    }
+
+    fn get(&self, x: isize, y: isize) -> u32 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        match &self.pixels {
+            Pixels::Display(display, palette) => {
+                let active = display.get_pixel(y, x);
+                match palette {
+                    Palette::Mono(colors) if active => colors.foreground,
+                    Palette::Mono(colors) => colors.background,
+                    // Die Farbschicht deckt nur die 32 Zeilen von CHIP-8X ab
+                    Palette::Layer(layer) => layer.color(y % DISPLAY_HEIGHT, x, active),
+                }
+            }
+            Pixels::Rgb(pixels) => pixels[y * self.width + x],
+        }
+    }
+}
+
+// Schreibt die `scale` x `scale` Pixel eines Quellpixels
+struct Target<'a> {
+    output: &'a mut [u8],
+    width: usize,
+    scale: usize,
+}
+
+impl Target<'_> {
+    fn set(&mut self, x: isize, y: isize, dx: usize, dy: usize, color: u32) {
+        let index = ((y as usize * self.scale + dy) * self.width + x as usize * self.scale + dx) * 4;
+        let [_, r, g, b] = color.to_be_bytes();
+        self.output[index..index + 4].copy_from_slice(&[r, g, b, 0xFF]);
+    }
+
+    fn fill(&mut self, x: isize, y: isize, color: u32) {
+        for dy in 0..self.scale {
+            for dx in 0..self.scale {
+                self.set(x, y, dx, dy, color);
+            }
+        }
+    }
+}
+
+// Nachbarn:  A B C
+//            D E F
+//            G H I
+fn scale2x(source: &Source, target: &mut Target, x: isize, y: isize) {
+    let (b, d, e, f, h) = (source.get(x, y - 1), source.get(x - 1, y), source.get(x, y), source.get(x + 1, y), source.get(x, y + 1));
+    if b != h && d != f {
+        target.set(x, y, 0, 0, if d == b { d } else { e });
+        target.set(x, y, 1, 0, if b == f { f } else { e });
+        target.set(x, y, 0, 1, if d == h { d } else { e });
+        target.set(x, y, 1, 1, if h == f { f } else { e });
+    } else {
+        target.fill(x, y, e);
+    }
+}
+
+fn scale3x(source: &Source, target: &mut Target, x: isize, y: isize) {
+    let [a, b, c, d, e, f, g, h, i] = neighbours(source, x, y);
+    let mut block = [e; 9];
+    if b != h && d != f {
+        block[0] = if d == b { d } else { e };
+        block[1] = if (d == b && e != c) || (b == f && e != a) { b } else { e };
+        block[2] = if b == f { f } else { e };
+        block[3] = if (d == b && e != g) || (d == h && e != a) { d } else { e };
+        block[5] = if (b == f && e != i) || (h == f && e != c) { f } else { e };
+        block[6] = if d == h { d } else { e };
+        block[7] = if (d == h && e != i) || (h == f && e != g) { h } else { e };
+        block[8] = if h == f { f } else { e };
+    }
+    for (index, color) in block.iter().enumerate() {
+        target.set(x, y, index % 3, index / 3, *color);
+    }
+}
+
+fn epx(source: &Source, target: &mut Target, x: isize, y: isize) {
+    let (a, b, c, d, p) = (source.get(x, y - 1), source.get(x + 1, y), source.get(x - 1, y), source.get(x, y + 1), source.get(x, y));
+    let mut block = [p; 4];
+    // Drei oder mehr gleiche Nachbarn: Das Pixel bleibt, wie es ist
+    let same = [(a, b), (a, c), (a, d), (b, c), (b, d), (c, d)].iter().filter(|(first, second)| first == second).count();
+    if same < 3 {
+        if c == a {
+            block[0] = a;
+        }
+        if a == b {
+            block[1] = b;
+        }
+        if d == c {
+            block[2] = c;
+        }
+        if b == d {
+            block[3] = d;
+        }
+    }
+    for (index, color) in block.iter().enumerate() {
+        target.set(x, y, index % 2, index / 2, *color);
+    }
+}
+
+// Für jede der vier Ecken wird geprüft, ob eine diagonale Kante durch sie verläuft. Die
+// Ecke wird dann entlang der Kante abgeschnitten und mit der Farbe des Nachbarn gefüllt
+fn xbr(source: &Source, target: &mut Target, x: isize, y: isize) {
+    let e = source.get(x, y);
+    let mut block = [[e; 4]; 4];
+    // Ecken als Richtung (rechts unten, links unten, rechts oben, links oben)
+    for (sx, sy) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+        // Nachbarn aus Sicht der Ecke rechts unten, gespiegelt für die anderen Ecken
+        let get = |dx: isize, dy: isize| source.get(x + dx * sx, y + dy * sy);
+        let (f, h, i) = (get(1, 0), get(0, 1), get(1, 1));
+        if e == f || e == h {
+            continue;
+        }
+        let (b, c, d, g) = (get(0, -1), get(1, -1), get(-1, 0), get(-1, 1));
+        let (f4, h5, i4, i5) = (get(2, 0), get(0, 2), get(2, 1), get(1, 2));
+        let edge = distance(e, c) + distance(e, g) + distance(i, f4) + distance(i, h5) + 4 * distance(h, f);
+        let across = distance(h, d) + distance(h, i5) + distance(f, i4) + distance(f, b) + 4 * distance(e, i);
+        if edge >= across {
+            continue;
+        }
+        let color = if distance(e, f) <= distance(e, h) { f } else { h };
+        // Abdeckung der Ecke: Die Kante verläuft durch die Mitte der beiden angrenzenden Seiten
+        for (dy, row) in block.iter_mut().enumerate() {
+            for (dx, pixel) in row.iter_mut().enumerate() {
+                let u = if sx > 0 { dx } else { 3 - dx };
+                let v = if sy > 0 { dy } else { 3 - dy };
+                // Pixel mit `u + v == 5` liegen genau auf der Kante
+                match u + v {
+                    5 => *pixel = blend(*pixel, color),
+                    6 => *pixel = color,
+                    _ => {}
+                }
+            }
+        }
+    }
+    for (dy, row) in block.iter().enumerate() {
+        for (dx, color) in row.iter().enumerate() {
+            target.set(x, y, dx, dy, *color);
+        }
+    }
+}
+
+fn crt(source: &Source, target: &mut Target, x: isize, y: isize) {
+    let color = source.get(x, y);
+    for dy in 0..4 {
+        for dx in 0..4 {
+            // Streifenmaske über das ganze Bild: Jede Spalte betont einen Farbkanal
+            let channel = (x as usize * 4 + dx) % 3;
+            let mut bytes = color.to_be_bytes();
+            for (index, value) in bytes[1..].iter_mut().enumerate() {
+                let factor = if index == channel { 4 } else { 3 };
+                *value = (u32::from(*value) * factor / 4) as u8;
+            }
+            // Die letzte Zeile jedes Pixels ist die dunkle Lücke zwischen den Zeilen
+            if dy == 3 {
+                bytes.iter_mut().for_each(|value| *value = (u32::from(*value) * 2 / 5) as u8);
+            }
+            target.set(x, y, dx, dy, u32::from_be_bytes(bytes));
+        }
+    }
+}
+
+fn neighbours(source: &Source, x: isize, y: isize) -> [u32; 9] {
+    let mut colors = [0; 9];
+    for (index, color) in colors.iter_mut().enumerate() {
+        *color = source.get(x + index as isize % 3 - 1, y + index as isize / 3 - 1);
+    }
+    colors
+}
+
+// Unterschied zweier Farben, Helligkeit zählt dabei mehr als der Farbton
+fn distance(a: u32, b: u32) -> u32 {
+    let [_, r1, g1, b1] = a.to_be_bytes();
+    let [_, r2, g2, b2] = b.to_be_bytes();
+    let (dr, dg, db) = (i32::from(r1) - i32::from(r2), i32::from(g1) - i32::from(g2), i32::from(b1) - i32::from(b2));
+    let y = (299 * dr + 587 * dg + 114 * db).unsigned_abs() / 1000;
+    let u = (-169 * dr - 331 * dg + 500 * db).unsigned_abs() / 1000;
+    let v = (500 * dr - 419 * dg - 81 * db).unsigned_abs() / 1000;
+    48 * y + 7 * u + 6 * v
+}
+
+// Mittelwert zweier Farben
+fn blend(a: u32, b: u32) -> u32 {
+    ((a & 0xFEFEFE) >> 1) + ((b & 0xFEFEFE) >> 1) + (a & b & 0x010101)
+}
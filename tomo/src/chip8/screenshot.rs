@@ -0,0 +1,235 @@
+//! Bildschirmfotos des Displays als PNG und PBM
+//!
+//! Die Kodierer brauchen weder Canvas noch weitere Abhängigkeiten. PNGs werden als
+//! Palettenbild mit 1 Bit pro Pixel gespeichert und mit festen Huffman-Codes komprimiert,
+//! Wiederholungen innerhalb einer Zeile und ganze wiederholte Zeilen (durch `scale`)
+//! werden dabei als Verweise abgelegt.
+
+use alloc::vec::Vec;
+
+use super::{Colors, Display, DISPLAY_WIDTH};
+use crate::prelude::*;
+
+// Größter Vergrößerungsfaktor (64 * 32 = 2048 Pixel Breite)
+pub const MAX_SCALE: u32 = 32;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+#[wasm_bindgen]
+impl Display {
+    // PNG mit Hintergrund- und Vordergrundfarbe, jedes Pixel wird `scale` x `scale` groß
+    pub fn to_png(&self, colors: &Colors, scale: u32) -> Vec<u8> {
+        let scale = clamp_scale(scale);
+        let (width, height) = (DISPLAY_WIDTH as u32 * scale, self.height() as u32 * scale);
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // Bittiefe 1, Palette, Deflate, Standardfilter, kein Interlacing
+        header.extend_from_slice(&[1, 3, 0, 0, 0]);
+
+        let mut palette = Vec::with_capacity(6);
+        for color in [colors.background, colors.foreground] {
+            palette.extend_from_slice(&color.to_be_bytes()[1..]);
+        }
+
+        // Jede Zeile beginnt mit dem Filtertyp 0 (keiner)
+        let stride = 1 + self.packed_row(0, scale).len();
+        let mut pixels = Vec::with_capacity(stride * height as usize);
+        for y in 0..self.height() {
+            let row = self.packed_row(y, scale);
+            for _ in 0..scale {
+                pixels.push(0);
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"PLTE", &palette);
+        write_chunk(&mut png, b"IDAT", &zlib(&pixels, stride));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    // PBM im Textformat ("P1"), aktive Pixel sind schwarz (1)
+    pub fn to_pbm_plain(&self, scale: u32) -> Vec<u8> {
+        let scale = clamp_scale(scale) as usize;
+        let width = DISPLAY_WIDTH * scale;
+        let mut pbm = f!("P1\n{} {}\n", width, self.height() * scale).into_bytes();
+        for y in 0..self.height() * scale {
+            // Zeilen im Format dürfen höchstens 70 Zeichen lang sein
+            for x in 0..width {
+                pbm.push(if self.get_pixel(y / scale, x / scale) { b'1' } else { b'0' });
+                pbm.push(if x % 35 == 34 || x == width - 1 { b'\n' } else { b' ' });
+            }
+        }
+        pbm
+    }
+
+    // PBM im Binärformat ("P4"), 8 Pixel pro Byte
+    pub fn to_pbm_raw(&self, scale: u32) -> Vec<u8> {
+        let scale = clamp_scale(scale);
+        let mut pbm = f!("P4\n{} {}\n", DISPLAY_WIDTH as u32 * scale, self.height() as u32 * scale).into_bytes();
+        for y in 0..self.height() {
+            let row = self.packed_row(y, scale);
+            for _ in 0..scale {
+                pbm.extend_from_slice(&row);
+            }
+        }
+        pbm
+    }
+}
+
+impl Display {
+    // Zeile mit 1 Bit pro Pixel (höchstwertiges zuerst), horizontal um `scale` vergrößert
+    fn packed_row(&self, y: usize, scale: u32) -> Vec<u8> {
+        let width = DISPLAY_WIDTH * scale as usize;
+        let mut row = alloc::vec![0u8; width.div_ceil(8)];
+        for x in 0..width {
+            if self.get_pixel(y, x / scale as usize) {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        row
+    }
+}
+
+fn clamp_scale(scale: u32) -> u32 {
+    scale.clamp(1, MAX_SCALE)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += u32::from(*byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+// Schreibt Bits, niederwertigste zuerst
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, size: u32) {
+        self.buffer |= value << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    // Huffman-Codes werden mit dem höchstwertigen Bit zuerst geschrieben
+    fn write_code(&mut self, code: u32, size: u32) {
+        self.write(code.reverse_bits() >> (32 - size), size);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// Symbol mit den festen Huffman-Codes (RFC 1951, 3.2.6)
+fn write_symbol(writer: &mut BitWriter, symbol: u16) {
+    let symbol = u32::from(symbol);
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let index = LENGTH_BASE.iter().rposition(|base| usize::from(*base) <= length).unwrap_or(0);
+    write_symbol(writer, 257 + index as u16);
+    writer.write((length - usize::from(LENGTH_BASE[index])) as u32, u32::from(LENGTH_EXTRA[index]));
+    let index = DISTANCE_BASE.iter().rposition(|base| usize::from(*base) <= distance).unwrap_or(0);
+    writer.write_code(index as u32, 5);
+    writer.write((distance - usize::from(DISTANCE_BASE[index])) as u32, u32::from(DISTANCE_EXTRA[index]));
+}
+
+// Zlib-Datenstrom mit einem Deflate-Block. Als Verweise werden nur das vorherige Byte und
+// die vorherige Zeile (`stride`) probiert, das genügt für Bildschirmfotos
+fn zlib(data: &[u8], stride: usize) -> Vec<u8> {
+    let mut writer = BitWriter { bytes: alloc::vec![0x78, 0x01], buffer: 0, bits: 0 };
+    // Letzter Block, feste Huffman-Codes
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let mut position = 0;
+    while position < data.len() {
+        let best = [1, stride]
+            .iter()
+            .filter(|distance| **distance <= position && **distance <= 32768)
+            .map(|distance| {
+                let length = data[position..]
+                    .iter()
+                    .zip(&data[position - distance..])
+                    .take(258)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                (length, *distance)
+            })
+            .max_by_key(|(length, _)| *length);
+        match best {
+            Some((length, distance)) if length >= 3 => {
+                write_match(&mut writer, length, distance);
+                position += length;
+            }
+            _ => {
+                write_symbol(&mut writer, u16::from(data[position]));
+                position += 1;
+            }
+        }
+    }
+    write_symbol(&mut writer, 256);
+
+    let mut zlib = writer.finish();
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
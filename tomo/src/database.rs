@@ -0,0 +1,285 @@
+//! ROM-Erkennung über die Community-Datenbank (chip-8-database)
+//!
+//! ROMs werden über ihren SHA-1 Hash erkannt. Die Datenbank liegt eingebettet im
+//! Ordner `data/chip-8-database` und kann durch neuere Versionen der Dateien
+//! `programs.json`, `sha1-hashes.json` und `platforms.json` ersetzt oder zur
+//! Laufzeit über `RomDatabase::from_json` geladen werden.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::chip8::analyzer::InstructionSet;
+use crate::chip8::quirks::Quirks;
+use crate::chip8::Colors;
+use crate::prelude::*;
+
+const EMBEDDED_PROGRAMS: &str = include_str!("../data/chip-8-database/programs.json");
+const EMBEDDED_HASHES: &str = include_str!("../data/chip-8-database/sha1-hashes.json");
+const EMBEDDED_PLATFORMS: &str = include_str!("../data/chip-8-database/platforms.json");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseError {
+    // Eine der JSON-Dateien ist ungültig
+    Json { file: &'static str, message: String },
+    // Ein Hash zeigt auf ein Programm, das es nicht gibt
+    MissingProgram { sha1: String, index: usize },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::Json { file, message } => write!(f, "Invalid {}: {}", file, message),
+            DatabaseError::MissingProgram { sha1, index } => {
+                write!(f, "Hash {} points to the missing program #{}", sha1, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<DatabaseError> for JsValue {
+    fn from(error: DatabaseError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Quirks wie in der Datenbank, fehlende Einträge gelten als "false"
+// bzw. werden bei Überschreibungen nicht verändert
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+struct QuirkSet {
+    shift: Option<bool>,
+    memory_increment_by_x: Option<bool>,
+    memory_leave_i_unchanged: Option<bool>,
+    wrap: Option<bool>,
+    jump: Option<bool>,
+    vblank: Option<bool>,
+    logic: Option<bool>,
+}
+
+impl QuirkSet {
+    fn apply(&self, quirks: &mut Quirks) {
+        let fields = [
+            (self.shift, &mut quirks.shift),
+            (self.memory_increment_by_x, &mut quirks.memory_increment_by_x),
+            (self.memory_leave_i_unchanged, &mut quirks.memory_leave_i_unchanged),
+            (self.wrap, &mut quirks.wrap),
+            (self.jump, &mut quirks.jump),
+            (self.vblank, &mut quirks.vblank),
+            (self.logic, &mut quirks.logic),
+        ];
+        for (value, quirk) in fields {
+            if let Some(value) = value {
+                *quirk = value;
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PlatformEntry {
+    id: String,
+    name: String,
+    default_tickrate: u16,
+    quirks: QuirkSet,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ColorEntry {
+    #[serde(default)]
+    pixels: Vec<String>,
+    buzzer: Option<String>,
+    silence: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RomEntry {
+    file: Option<String>,
+    embedded_title: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    #[serde(default)]
+    platforms: Vec<String>,
+    #[serde(default)]
+    quirky_platforms: HashMap<String, QuirkSet>,
+    tickrate: Option<u16>,
+    colors: Option<ColorEntry>,
+    #[serde(default)]
+    keys: BTreeMap<String, u8>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ProgramEntry {
+    title: String,
+    #[serde(default)]
+    authors: Vec<String>,
+    roms: HashMap<String, RomEntry>,
+}
+
+// Zuordnung einer Aktion (z.B. "up" oder "a") zu einer CHIP-8 Taste
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomKey {
+    pub action: String,
+    pub key: u8,
+}
+
+// Informationen und empfohlene Einstellungen zu einer erkannten ROM
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RomInfo {
+    pub sha1: String,
+    pub title: String,
+    // Titel, wie er in der ROM selbst steht
+    pub embedded_title: Option<String>,
+    pub file: Option<String>,
+    pub authors: Vec<String>,
+    // Id der bevorzugten Plattform, z.B. "originalChip8"
+    pub platform: String,
+    pub platform_name: String,
+    pub quirks: Quirks,
+    pub tick_rate: u16,
+    pub colors: Option<Colors>,
+    pub keys: Vec<RomKey>,
+}
+
+#[wasm_bindgen]
+impl RomInfo {
+    // Befehlssatz der bevorzugten Plattform, `None` bei einer unbekannten Plattform
+    pub fn instruction_set(&self) -> Option<InstructionSet> {
+        match self.platform.as_str() {
+            "originalChip8" | "hybridVIP" | "modernChip8" => Some(InstructionSet::Chip8),
+            "chip8x" => Some(InstructionSet::Chip8x),
+            "chip48" | "superchip1" | "superchip" => Some(InstructionSet::Schip),
+            "megachip8" => Some(InstructionSet::MegaChip),
+            "xochip" => Some(InstructionSet::XoChip),
+            _ => None,
+        }
+    }
+
+    // Ob die ROM Maschinensprache des VIP aufruft (0NNN)
+    pub fn machine_code(&self) -> bool {
+        self.platform == "hybridVIP"
+    }
+}
+
+pub struct RomDatabase {
+    programs: Vec<ProgramEntry>,
+    hashes: HashMap<String, usize>,
+    platforms: Vec<PlatformEntry>,
+}
+
+impl RomDatabase {
+    // Lädt die Datenbank aus den drei JSON-Dateien der chip-8-database
+    pub fn from_json(programs: &str, hashes: &str, platforms: &str) -> Result<Self, DatabaseError> {
+        let programs: Vec<ProgramEntry> = parse("programs.json", programs)?;
+        let hashes: HashMap<String, usize> = parse("sha1-hashes.json", hashes)?;
+        let platforms: Vec<PlatformEntry> = parse("platforms.json", platforms)?;
+
+        if let Some((sha1, index)) = hashes.iter().find(|(_, index)| **index >= programs.len()) {
+            return Err(DatabaseError::MissingProgram { sha1: sha1.clone(), index: *index });
+        }
+
+        Ok(RomDatabase {
+            programs,
+            hashes,
+            platforms,
+        })
+    }
+
+    // Eingebettete Datenbank, wird beim ersten Zugriff geladen
+    pub fn embedded() -> &'static RomDatabase {
+        static EMBEDDED: OnceLock<RomDatabase> = OnceLock::new();
+        EMBEDDED.get_or_init(|| {
+            RomDatabase::from_json(EMBEDDED_PROGRAMS, EMBEDDED_HASHES, EMBEDDED_PLATFORMS)
+                .expect("The embedded chip-8-database is invalid")
+        })
+    }
+
+    // Anzahl bekannter ROMs
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    // Erkennt eine ROM anhand ihres Inhalts
+    pub fn identify(&self, rom: &[u8]) -> Option<RomInfo> {
+        self.lookup(&sha1(rom))
+    }
+
+    // Sucht eine ROM über ihren SHA-1 Hash (hexadezimal)
+    pub fn lookup(&self, sha1: &str) -> Option<RomInfo> {
+        let sha1 = sha1.to_ascii_lowercase();
+        let program = &self.programs[*self.hashes.get(&sha1)?];
+        let rom = program.roms.get(&sha1)?;
+
+        let platform = rom
+            .platforms
+            .first()
+            .and_then(|id| self.platforms.iter().find(|platform| &platform.id == id));
+
+        // Quirks der Plattform, ggf. überschrieben durch die ROM
+        // (`Quirks::modern` hat alle Quirks ausgeschaltet)
+        let mut quirks = Quirks::modern();
+        if let Some(platform) = platform {
+            platform.quirks.apply(&mut quirks);
+            if let Some(overrides) = rom.quirky_platforms.get(&platform.id) {
+                overrides.apply(&mut quirks);
+            }
+        }
+
+        let authors = if rom.authors.is_empty() { &program.authors } else { &rom.authors };
+
+        Some(RomInfo {
+            title: program.title.clone(),
+            embedded_title: rom.embedded_title.clone(),
+            file: rom.file.clone(),
+            authors: authors.clone(),
+            platform: platform.map(|platform| platform.id.clone()).unwrap_or_default(),
+            platform_name: platform.map(|platform| platform.name.clone()).unwrap_or_default(),
+            quirks,
+            tick_rate: rom
+                .tickrate
+                .or_else(|| platform.map(|platform| platform.default_tickrate))
+                .unwrap_or(crate::chip8::DEFAULT_TICK_RATE),
+            colors: rom.colors.as_ref().and_then(parse_colors),
+            keys: rom
+                .keys
+                .iter()
+                .map(|(action, key)| RomKey { action: action.clone(), key: *key })
+                .collect(),
+            sha1,
+        })
+    }
+}
+
+// SHA-1 Hash einer ROM als hexadezimale Zeichenkette
+pub fn sha1(rom: &[u8]) -> String {
+    sha1_smol::Sha1::from(rom).digest().to_string()
+}
+
+fn parse<T: serde::de::DeserializeOwned>(file: &'static str, json: &str) -> Result<T, DatabaseError> {
+    serde_json::from_str(json).map_err(|error| DatabaseError::Json {
+        file,
+        message: error.to_string(),
+    })
+}
+
+fn parse_colors(colors: &ColorEntry) -> Option<Colors> {
+    let default = Colors::default();
+    Some(Colors {
+        background: Colors::parse_hex(colors.pixels.first()?)?,
+        foreground: Colors::parse_hex(colors.pixels.get(1)?)?,
+        buzzer: colors.buzzer.as_deref().and_then(Colors::parse_hex).unwrap_or(default.buzzer),
+        silence: colors.silence.as_deref().and_then(Colors::parse_hex).unwrap_or(default.silence),
+    })
+}
@@ -0,0 +1,295 @@
+//! Tastaturbelegung: Übersetzt Tasten des Hosts in Tasten des Hex-Keypads
+//!
+//! Tasten werden über `KeyboardEvent.code` (z.B. "KeyQ", "Numpad5") angegeben. Diese Namen
+//! beschreiben die physische Position einer Taste, unabhängig von der Belegung des Systems.
+//! Native Frontends können stattdessen Scancodes (PC/AT Set 1, erweiterte Codes als 0xE0xx)
+//! übergeben. Die Layouts für AZERTY und QWERTZ belegen daher dieselben Positionen wie das
+//! Standard-Layout, beschriften die Tasten aber passend zur Tastatur.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::chip8::processor::Key;
+#[cfg(feature = "database")]
+use crate::database::RomInfo;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapError(pub String);
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid keymap: {}", self.0)
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+impl From<KeymapError> for JsValue {
+    fn from(error: KeymapError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Vorgegebene Belegungen
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Layout {
+    // 1234 / QWER / ASDF / ZXCV
+    Standard,
+    // Ziffern auf sich selbst, A-F auf / * - + Enter .
+    Numpad,
+    // 1234 / AZER / QSDF / WXCV
+    Azerty,
+    // 1234 / QWER / ASDF / YXCV
+    Qwertz,
+}
+
+// Tasten in der Reihenfolge des Werts, den der Processor für sie verwendet (`Key as u8`).
+// Die Namen der Tasten entsprechen nicht überall ihrem Wert: `K1` ist 0x0, `K0` ist 0x9
+const VALUES: [Key; 16] = [
+    Key::K1, Key::K2, Key::K3, Key::K4, Key::K5, Key::K6, Key::K7, Key::K8,
+    Key::K9, Key::K0, Key::KA, Key::KB, Key::KC, Key::KD, Key::KE, Key::KF,
+];
+
+// Anordnung des COSMAC VIP Keypads als Hex-Werte, Zeile für Zeile
+const KEYPAD: [u8; 16] = [
+    0x1, 0x2, 0x3, 0xC,
+    0x4, 0x5, 0x6, 0xD,
+    0x7, 0x8, 0x9, 0xE,
+    0xA, 0x0, 0xB, 0xF,
+];
+
+// Der 4x4 Block links oben auf der Tastatur
+const GRID: [&str; 16] = [
+    "Digit1", "Digit2", "Digit3", "Digit4",
+    "KeyQ", "KeyW", "KeyE", "KeyR",
+    "KeyA", "KeyS", "KeyD", "KeyF",
+    "KeyZ", "KeyX", "KeyC", "KeyV",
+];
+
+const NUMPAD: [(&str, u8); 16] = [
+    ("Numpad0", 0x0), ("Numpad1", 0x1), ("Numpad2", 0x2), ("Numpad3", 0x3),
+    ("Numpad4", 0x4), ("Numpad5", 0x5), ("Numpad6", 0x6), ("Numpad7", 0x7),
+    ("Numpad8", 0x8), ("Numpad9", 0x9), ("NumpadDivide", 0xA), ("NumpadMultiply", 0xB),
+    ("NumpadSubtract", 0xC), ("NumpadAdd", 0xD), ("NumpadEnter", 0xE), ("NumpadDecimal", 0xF),
+];
+
+// Scancodes (PC/AT Set 1) der Tasten, die `KeyboardEvent.code` benennt
+const SCANCODES: [(u16, &str); 63] = [
+    (0x02, "Digit1"), (0x03, "Digit2"), (0x04, "Digit3"), (0x05, "Digit4"), (0x06, "Digit5"),
+    (0x07, "Digit6"), (0x08, "Digit7"), (0x09, "Digit8"), (0x0A, "Digit9"), (0x0B, "Digit0"),
+    (0x10, "KeyQ"), (0x11, "KeyW"), (0x12, "KeyE"), (0x13, "KeyR"), (0x14, "KeyT"),
+    (0x15, "KeyY"), (0x16, "KeyU"), (0x17, "KeyI"), (0x18, "KeyO"), (0x19, "KeyP"),
+    (0x1C, "Enter"), (0x1E, "KeyA"), (0x1F, "KeyS"), (0x20, "KeyD"), (0x21, "KeyF"),
+    (0x22, "KeyG"), (0x23, "KeyH"), (0x24, "KeyJ"), (0x25, "KeyK"), (0x26, "KeyL"),
+    (0x27, "Semicolon"), (0x2A, "ShiftLeft"), (0x2C, "KeyZ"), (0x2D, "KeyX"), (0x2E, "KeyC"),
+    (0x2F, "KeyV"), (0x30, "KeyB"), (0x31, "KeyN"), (0x32, "KeyM"), (0x33, "Comma"),
+    (0x34, "Period"), (0x36, "ShiftRight"), (0x37, "NumpadMultiply"), (0x39, "Space"),
+    (0x47, "Numpad7"), (0x48, "Numpad8"), (0x49, "Numpad9"), (0x4A, "NumpadSubtract"),
+    (0x4B, "Numpad4"), (0x4C, "Numpad5"), (0x4D, "Numpad6"), (0x4E, "NumpadAdd"),
+    (0x4F, "Numpad1"), (0x50, "Numpad2"), (0x51, "Numpad3"), (0x52, "Numpad0"),
+    (0x53, "NumpadDecimal"), (0xE01C, "NumpadEnter"), (0xE035, "NumpadDivide"),
+    (0xE048, "ArrowUp"), (0xE04B, "ArrowLeft"), (0xE04D, "ArrowRight"), (0xE050, "ArrowDown"),
+];
+
+// Tasten für die Aktionen der chip-8-database
+#[cfg(feature = "database")]
+const ACTIONS: [(&str, &str); 6] = [
+    ("up", "ArrowUp"),
+    ("down", "ArrowDown"),
+    ("left", "ArrowLeft"),
+    ("right", "ArrowRight"),
+    ("a", "Space"),
+    ("b", "Enter"),
+];
+
+// Taste, die im Processor den Hex-Wert `value` (z.B. für `EX9E`) auslöst
+pub fn key_from_hex(value: u8) -> Option<Key> {
+    VALUES.get(usize::from(value)).copied()
+}
+
+// Name der Taste nach `KeyboardEvent.code` für einen Scancode
+pub fn scancode_to_code(scancode: u16) -> Option<&'static str> {
+    SCANCODES.iter().find(|(code, _)| *code == scancode).map(|(_, name)| *name)
+}
+
+// Belegung des Hex-Keypads mit Tasten des Hosts
+#[wasm_bindgen]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Keymap {
+    layout: Layout,
+    bindings: BTreeMap<String, Key>,
+    // Abweichende Tasten für einzelne ROMs (z.B. nach SHA-1), haben Vorrang vor `bindings`
+    #[serde(default)]
+    rom_bindings: BTreeMap<String, BTreeMap<String, Key>>,
+    // Aktuell gespielte ROM
+    #[serde(skip)]
+    rom: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Keymap {
+    #[wasm_bindgen(constructor)]
+    pub fn new(layout: Layout) -> Self {
+        let mut keymap = Keymap {
+            layout,
+            bindings: BTreeMap::new(),
+            rom_bindings: BTreeMap::new(),
+            rom: None,
+        };
+        keymap.set_layout(layout);
+        keymap
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    // Setzt alle Tasten auf das Layout zurück, ROM-Belegungen bleiben erhalten
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.bindings = match layout {
+            Layout::Numpad => NUMPAD.iter().map(|(code, value)| (code.to_string(), VALUES[usize::from(*value)])).collect(),
+            _ => GRID.iter().zip(KEYPAD).map(|(code, value)| (code.to_string(), VALUES[usize::from(value)])).collect(),
+        };
+    }
+
+    // Taste zu `KeyboardEvent.code`
+    pub fn map_code(&self, code: &str) -> Option<Key> {
+        let rom = self.rom.as_ref().and_then(|rom| self.rom_bindings.get(rom));
+        rom.and_then(|bindings| bindings.get(code)).or_else(|| self.bindings.get(code)).copied()
+    }
+
+    pub fn map_scancode(&self, scancode: u16) -> Option<Key> {
+        scancode_to_code(scancode).and_then(|code| self.map_code(code))
+    }
+
+    // Belegt eine weitere Taste, bereits belegte Tasten des Keypads bleiben belegt
+    pub fn bind(&mut self, code: &str, key: Key) {
+        self.bindings.insert(code.to_string(), key);
+    }
+
+    // Ersetzt alle Tasten, die bisher `key` auslösen, durch `code`
+    pub fn rebind(&mut self, key: Key, code: &str) {
+        self.bindings.retain(|_, bound| *bound != key);
+        self.bind(code, key);
+    }
+
+    pub fn unbind(&mut self, code: &str) {
+        self.bindings.remove(code);
+    }
+
+    // Alle Tasten, die `key` auslösen (ohne ROM-Belegungen)
+    pub fn codes(&self, key: Key) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| **bound == key)
+            .map(|(code, _)| code.clone())
+            .collect()
+    }
+
+    // Beschriftung der ersten Taste für `key`, z.B. für eine Bildschirmtastatur
+    pub fn label(&self, key: Key) -> Option<String> {
+        let first = |bindings: &BTreeMap<String, Key>| {
+            bindings.iter().find(|(_, bound)| **bound == key).map(|(code, _)| code.clone())
+        };
+        let rom = self.rom.as_ref().and_then(|rom| self.rom_bindings.get(rom));
+        rom.and_then(first).or_else(|| first(&self.bindings)).map(|code| self.code_label(&code))
+    }
+
+    // Beschriftung einer Taste auf der Tastatur des Layouts
+    pub fn code_label(&self, code: &str) -> String {
+        let label = match (self.layout, code) {
+            (Layout::Azerty, "KeyQ") => "A",
+            (Layout::Azerty, "KeyA") => "Q",
+            (Layout::Azerty, "KeyW") => "Z",
+            (Layout::Azerty, "KeyZ") => "W",
+            (Layout::Azerty, "Semicolon") => "M",
+            (Layout::Azerty, "KeyM") => ",",
+            (Layout::Qwertz, "KeyZ") => "Y",
+            (Layout::Qwertz, "KeyY") => "Z",
+            (_, "NumpadDivide") => "/",
+            (_, "NumpadMultiply") => "*",
+            (_, "NumpadSubtract") => "-",
+            (_, "NumpadAdd") => "+",
+            (_, "NumpadDecimal") => ".",
+            (_, "NumpadEnter") => "Enter",
+            _ => {
+                let name = code.strip_prefix("Key").or_else(|| code.strip_prefix("Digit"));
+                return match (name, code.strip_prefix("Numpad")) {
+                    (Some(name), _) => name.to_string(),
+                    (None, Some(name)) => f!("Num {}", name),
+                    (None, None) => code.to_string(),
+                };
+            }
+        };
+        label.to_string()
+    }
+
+    // Wählt die ROM, deren eigene Belegung gelten soll (`None` für keine)
+    pub fn select_rom(&mut self, rom: Option<String>) {
+        self.rom = rom;
+    }
+
+    pub fn selected_rom(&self) -> Option<String> {
+        self.rom.clone()
+    }
+
+    pub fn bind_for_rom(&mut self, rom: &str, code: &str, key: Key) {
+        self.rom_bindings.entry(rom.to_string()).or_default().insert(code.to_string(), key);
+    }
+
+    pub fn clear_rom(&mut self, rom: &str) {
+        self.rom_bindings.remove(rom);
+    }
+
+    // Belegt die Aktionen aus der chip-8-database (Pfeiltasten, Leertaste, Enter) für die ROM
+    #[cfg(feature = "database")]
+    pub fn bind_rom_keys(&mut self, info: &RomInfo) {
+        for rom_key in &info.keys {
+            let code = ACTIONS.iter().find(|(action, _)| *action == rom_key.action);
+            if let (Some((_, code)), Some(key)) = (code, key_from_hex(rom_key.key)) {
+                self.bind_for_rom(&info.sha1, code, key);
+            }
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Keymap, KeymapError> {
+        serde_json::from_str(json).map_err(|error| KeymapError(error.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Keymaps are always serializable")
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(Layout::Standard)
+    }
+}
+
+// Tasten werden als Hex-Ziffer ihres Werts ("0" bis "F") gespeichert
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&f!("{:X}", *self as u8))
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let digit = String::deserialize(deserializer)?;
+        u8::from_str_radix(&digit, 16)
+            .ok()
+            .filter(|_| digit.len() == 1)
+            .and_then(key_from_hex)
+            .ok_or_else(|| de::Error::custom(f!("`{}` is not a key of the hex keypad", digit)))
+    }
+}
@@ -1,4 +1,17 @@
+// Ohne Feature "std" wird nur `core` (und optional `alloc`) genutzt
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod chip8;
+#[cfg(feature = "database")]
+pub mod database;
+#[cfg(feature = "keymap")]
+pub mod keymap;
+#[cfg(feature = "octo")]
+pub mod octo;
+pub mod platform;
 pub mod prelude;
 pub mod reporting;
 
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use super::gif::decode_frames;
+use super::{compile, CartridgeError, OctoOptions};
+
+// Inhalt einer Cartridge, so wie Octo ihn speichert
+#[derive(Deserialize)]
+struct Payload {
+    program: String,
+    #[serde(default)]
+    options: OctoOptions,
+}
+
+// Eine dekodierte Octo-Cartridge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cartridge {
+    // Quelltext des Programms in Octo-Syntax
+    pub program: String,
+    pub options: OctoOptions,
+}
+
+impl Cartridge {
+    // Liest Programm und Optionen aus einem Cartridge-GIF
+    pub fn decode(gif: &[u8]) -> Result<Self, CartridgeError> {
+        let frames = decode_frames(gif)?;
+
+        // Jeder Pixel trägt 2 Bit (höchstwertige zuerst), 4 Pixel ergeben ein Byte
+        let mut bytes = Vec::new();
+        let mut byte = 0u8;
+        for (index, pixel) in frames.iter().flat_map(|frame| &frame.indices).enumerate() {
+            byte = byte << 2 | (pixel & 0b11);
+            if index % 4 == 3 {
+                bytes.push(byte);
+            }
+        }
+
+        if bytes.len() < 4 {
+            return Err(CartridgeError::Payload("missing length"));
+        }
+        let length = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        let json = bytes
+            .get(4..4 + length)
+            .ok_or(CartridgeError::Payload("length exceeds the image data"))?;
+        let json = core::str::from_utf8(json).map_err(|_| CartridgeError::Payload("invalid UTF-8"))?;
+        let payload: Payload = serde_json::from_str(json).map_err(|error| CartridgeError::Json(error.to_string()))?;
+
+        Ok(Cartridge {
+            program: payload.program,
+            options: payload.options,
+        })
+    }
+
+    // Übersetzt den Quelltext in Bytes (ab 0x200)
+    pub fn rom(&self) -> Result<Vec<u8>, CartridgeError> {
+        Ok(compile(&self.program)?)
+    }
+}
@@ -0,0 +1,1049 @@
+// Compiler für Octo-Quelltexte, so wie sie in Cartridges stecken
+//
+// Unterstützt wird die Sprache von Octo 1.2: Labels (auch vorwärts), `:const`, `:alias`,
+// `:next`, `:unpack`, `:org`, `:byte`, `:pointer`, `:call`, alle Befehle von CHIP-8, SUPER-CHIP
+// und XO-CHIP, `if`/`then`, `if`/`begin`/`else`/`end`, `loop`/`while`/`again`, Makros,
+// `:calc`-Ausdrücke, `:assert` und `:stringmode`. Anweisungen für den Debugger von Octo
+// (`:breakpoint`, `:monitor`) werden übersprungen. Wie bei Octo beginnt das Programm bei 0x200 mit
+// einem Sprung zu `main`, der entfällt, wenn `main` ganz am Anfang steht.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::prelude::*;
+
+// Adresse, ab der Octo übersetzt
+const START: usize = 0x200;
+// Höchstzahl an Tokens, damit sich rekursive Makros nicht endlos ausbreiten
+const MAX_TOKENS: usize = 1 << 20;
+
+// Wörter mit fester Bedeutung, die nicht als Namen verwendet werden können
+const RESERVED: &[&str] = &[
+    ":=", "|=", "&=", "^=", "-=", "=-", "+=", ">>=", "<<=", "==", "!=", "<", ">", "<=", ">=", "key", "-key", "hex",
+    "bighex", "random", "delay", "buzzer", "pitch", ":", ":next", ":unpack", ":breakpoint", ":proto", ":alias",
+    ":const", ":org", ":macro", ":calc", ":byte", ":call", ":stringmode", ":assert", ":monitor", ":pointer", ";",
+    "return", "clear", "bcd", "save", "load", "saveflags", "loadflags", "if", "then", "begin", "else", "end", "jump",
+    "jump0", "native", "sprite", "loop", "while", "again", "scroll-down", "scroll-up", "scroll-right", "scroll-left",
+    "lores", "hires", "exit", "i", "audio", "plane", "long", "{", "}", "(", ")",
+];
+
+// Tasten der Tastatur, wie Octo sie auf das Keypad legt
+const KEYS: [(&str, u8); 16] = [
+    ("1", 0x1), ("2", 0x2), ("3", 0x3), ("4", 0xC),
+    ("Q", 0x4), ("W", 0x5), ("E", 0x6), ("R", 0xD),
+    ("A", 0x7), ("S", 0x8), ("D", 0x9), ("F", 0xE),
+    ("Z", 0xA), ("X", 0x0), ("C", 0xB), ("V", 0xF),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    // Zeile im Quelltext, ab 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+type Result<T> = core::result::Result<T, CompileError>;
+
+// Übersetzt einen Octo-Quelltext in eine ROM, die ab 0x200 geladen wird
+pub fn compile(source: &str) -> Result<Vec<u8>> {
+    Compiler::new(tokenize(source)?).run()
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    line: usize,
+    // In Anführungszeichen (für `:stringmode` und `:assert`)
+    string: bool,
+}
+
+// Zerlegt den Quelltext an Leerzeichen, "#" leitet einen Kommentar bis zum Zeilenende ein
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            _ if c.is_whitespace() => {}
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let start = line;
+                let missing = || CompileError { line: start, message: "Missing closing quote".into() };
+                let mut text = String::new();
+                loop {
+                    match chars.next().ok_or_else(missing)? {
+                        '"' => break,
+                        '\\' => text.push(match chars.next().ok_or_else(missing)? {
+                            'n' => '\n',
+                            'r' => '\r',
+                            't' => '\t',
+                            'v' => '\x0B',
+                            '0' => '\0',
+                            escaped => escaped,
+                        }),
+                        c => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                    }
+                }
+                tokens.push(Token { text, line: start, string: true });
+            }
+            _ => {
+                let mut text = String::from(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    text.push(c);
+                }
+                tokens.push(Token { text, line, string: false });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// Zahl wie in Octo: dezimal, "0x.." oder "0b..", optional mit "-"
+fn parse_number(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let (digits, radix) = if let Some(hex) = digits.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        (binary, 2)
+    } else {
+        (digits, 10)
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let value = i64::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+// v0 bis vF (Groß- und Kleinschreibung egal)
+fn register_index(text: &str) -> Option<u8> {
+    let digit = text.strip_prefix('v').or_else(|| text.strip_prefix('V'))?;
+    if digit.len() != 1 {
+        return None;
+    }
+    u8::from_str_radix(digit, 16).ok()
+}
+
+fn is_identifier(text: &str) -> bool {
+    !RESERVED.contains(&text) && register_index(text).is_none() && parse_number(text).is_none()
+}
+
+// Stelle, an der eine Adresse eingetragen wird, sobald sie feststeht
+#[derive(Debug, Clone, Copy)]
+enum Fixup {
+    // 12 Bit in einer Instruktion wie 1NNN oder ANNN
+    Short(usize),
+    // 16 Bit in zwei Bytes (`i := long`, `:pointer`)
+    Long(usize),
+    // Zwei 6XNN-Instruktionen von `:unpack` mit dem oberen Nibble (ohne bei `:unpack long`)
+    Unpack(usize, Option<u8>),
+}
+
+// Verweis auf ein Label, das erst später definiert wird
+struct Pending {
+    name: String,
+    fixup: Fixup,
+    line: usize,
+}
+
+enum Address {
+    Known(usize),
+    Forward(String),
+}
+
+#[derive(Clone)]
+struct Macro {
+    arguments: Vec<String>,
+    body: Vec<Token>,
+    // Bisherige Aufrufe, im Rumpf als `CALLS` verfügbar
+    calls: usize,
+}
+
+// Bedingung von `if` und `while`
+struct Condition {
+    x: u8,
+    comparison: String,
+    operand: Option<Operand>,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Value(u8),
+}
+
+struct Compiler {
+    tokens: Vec<Token>,
+    position: usize,
+    // Zeile des zuletzt gelesenen Tokens, für Fehlermeldungen
+    line: usize,
+    // Programm ab 0x200 und welche Bytes davon schon geschrieben wurden
+    rom: Vec<u8>,
+    written: Vec<bool>,
+    here: usize,
+    // Ob bei 0x200 noch ein Sprung zu `main` eingetragen werden muss
+    jump_to_main: bool,
+    labels: HashMap<String, usize>,
+    constants: HashMap<String, f64>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, Macro>,
+    // Je Zeichen die Position im Alphabet und der Rumpf
+    string_modes: HashMap<String, HashMap<char, (usize, Vec<Token>)>>,
+    pending: Vec<Pending>,
+    // Anfang (und Zeile) jeder offenen Schleife
+    loops: Vec<(usize, usize)>,
+    // Sprünge aus `while`, `None` trennt die Schleifen
+    whiles: Vec<Option<usize>>,
+    // Offene Sprünge von `begin` und `else`
+    branches: Vec<(usize, usize)>,
+}
+
+impl Compiler {
+    fn new(tokens: Vec<Token>) -> Self {
+        let aliases = [("compare-temp", 0xF), ("unpack-hi", 0x0), ("unpack-lo", 0x1)];
+        Compiler {
+            tokens,
+            position: 0,
+            line: 1,
+            rom: Vec::new(),
+            written: Vec::new(),
+            here: START,
+            jump_to_main: true,
+            labels: HashMap::new(),
+            constants: KEYS.iter().map(|(key, value)| (f!("OCTO_KEY_{}", key), f64::from(*value))).collect(),
+            aliases: aliases.iter().map(|(name, register)| (name.to_string(), *register)).collect(),
+            macros: HashMap::new(),
+            string_modes: HashMap::new(),
+            pending: Vec::new(),
+            loops: Vec::new(),
+            whiles: Vec::new(),
+            branches: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<u8>> {
+        // Platz für den Sprung zu `main`
+        self.instruction(0x10, 0x00)?;
+        while self.position < self.tokens.len() {
+            self.statement()?;
+        }
+
+        if let Some(pending) = self.pending.first() {
+            return Err(CompileError { line: pending.line, message: f!("Undefined name '{}'", pending.name) });
+        }
+        if let Some((_, line)) = self.loops.last() {
+            return Err(CompileError { line: *line, message: "This 'loop' does not have a matching 'again'".into() });
+        }
+        if let Some((_, line)) = self.branches.last() {
+            return Err(CompileError { line: *line, message: "This 'begin' does not have a matching 'end'".into() });
+        }
+        if self.jump_to_main {
+            match self.labels.get("main") {
+                Some(main) => self.patch(Fixup::Short(START), *main)?,
+                None => return self.error("The program does not define a 'main' label"),
+            }
+        }
+        Ok(self.rom)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T> {
+        Err(CompileError { line: self.line, message: message.into() })
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = match self.tokens.get(self.position) {
+            Some(token) => token.clone(),
+            None => return self.error("Unexpected end of the source"),
+        };
+        self.position += 1;
+        self.line = token.line;
+        Ok(token)
+    }
+
+    // Nächstes Wort (kein String) ohne es zu lesen
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).filter(|token| !token.string).map(|token| token.text.as_str())
+    }
+
+    fn peek_string(&self) -> bool {
+        self.tokens.get(self.position).is_some_and(|token| token.string)
+    }
+
+    fn peek_register(&self) -> bool {
+        self.peek().is_some_and(|text| self.register_of(text).is_some())
+    }
+
+    fn word(&mut self) -> Result<String> {
+        let token = self.next()?;
+        if token.string {
+            return self.error(f!("Unexpected string \"{}\"", token.text));
+        }
+        Ok(token.text)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let token = self.next()?;
+        if !token.string {
+            return self.error(f!("Expected a string, got '{}'", token.text));
+        }
+        Ok(token.text)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<()> {
+        let text = self.word()?;
+        if text != expected {
+            return self.error(f!("Expected '{}', got '{}'", expected, text));
+        }
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let name = self.word()?;
+        if !is_identifier(&name) {
+            return self.error(f!("'{}' cannot be used as a name", name));
+        }
+        Ok(name)
+    }
+
+    fn register_of(&self, text: &str) -> Option<u8> {
+        self.aliases.get(text).copied().or_else(|| register_index(text))
+    }
+
+    fn register(&mut self) -> Result<u8> {
+        let text = self.word()?;
+        match self.register_of(&text) {
+            Some(register) => Ok(register),
+            None => self.error(f!("Expected a register, got '{}'", text)),
+        }
+    }
+
+    fn alias(&self, name: &str) -> u8 {
+        self.aliases.get(name).copied().unwrap_or_default()
+    }
+
+    // Zahl oder Konstante
+    fn lookup(&self, text: &str) -> Option<i64> {
+        parse_number(text).or_else(|| self.constants.get(text).map(|value| *value as i64))
+    }
+
+    fn number(&mut self) -> Result<i64> {
+        if self.peek() == Some("{") {
+            return Ok(self.calc()? as i64);
+        }
+        let text = self.word()?;
+        match self.lookup(&text) {
+            Some(value) => Ok(value),
+            None => self.error(f!("Expected a number, got '{}'", text)),
+        }
+    }
+
+    fn byte_value(&mut self) -> Result<u8> {
+        let value = self.number()?;
+        self.fit_byte(value)
+    }
+
+    fn fit_byte(&self, value: i64) -> Result<u8> {
+        if (-128..=255).contains(&value) {
+            Ok(value as u8)
+        } else {
+            self.error(f!("The value {} does not fit into a byte", value))
+        }
+    }
+
+    fn nibble(&mut self) -> Result<u8> {
+        let value = self.number()?;
+        if (0..=0xF).contains(&value) {
+            Ok(value as u8)
+        } else {
+            self.error(f!("The value {} does not fit into a nibble", value))
+        }
+    }
+
+    fn address(&mut self) -> Result<Address> {
+        let text = self.word()?;
+        if let Some(value) = self.lookup(&text) {
+            if !(0..=0xFFFF).contains(&value) {
+                return self.error(f!("The address {} is out of range", value));
+            }
+            Ok(Address::Known(value as usize))
+        } else if let Some(address) = self.labels.get(&text) {
+            Ok(Address::Known(*address))
+        } else if is_identifier(&text) {
+            Ok(Address::Forward(text))
+        } else {
+            self.error(f!("Expected an address, got '{}'", text))
+        }
+    }
+
+    fn byte(&mut self, value: u8) -> Result<()> {
+        if self.here > 0xFFFF {
+            return self.error("The program does not fit into 64 KB");
+        }
+        let index = self.here - START;
+        if self.rom.len() <= index {
+            self.rom.resize(index + 1, 0);
+            self.written.resize(index + 1, false);
+        }
+        if self.written[index] {
+            return self.error(f!("Data overlap at address 0x{:04X}", self.here));
+        }
+        self.rom[index] = value;
+        self.written[index] = true;
+        self.here += 1;
+        Ok(())
+    }
+
+    fn instruction(&mut self, high: u8, low: u8) -> Result<()> {
+        self.byte(high)?;
+        self.byte(low)
+    }
+
+    // Instruktion mit 12-Bit-Adresse wie 1NNN, `prefix` ist das oberste Nibble
+    fn address_instruction(&mut self, prefix: u8, address: Address) -> Result<()> {
+        let at = self.here;
+        self.instruction(prefix << 4, 0x00)?;
+        self.reference(address, Fixup::Short(at))
+    }
+
+    // Trägt eine bekannte Adresse ein oder merkt sich die Stelle bis zur Definition
+    fn reference(&mut self, address: Address, fixup: Fixup) -> Result<()> {
+        match address {
+            Address::Known(address) => self.patch(fixup, address),
+            Address::Forward(name) => {
+                self.pending.push(Pending { name, fixup, line: self.line });
+                Ok(())
+            }
+        }
+    }
+
+    fn patch(&mut self, fixup: Fixup, address: usize) -> Result<()> {
+        match fixup {
+            Fixup::Short(at) => {
+                let address = self.short_address(address)?;
+                let index = at - START;
+                self.rom[index] = self.rom[index] & 0xF0 | (address >> 8) as u8;
+                self.rom[index + 1] = address as u8;
+            }
+            Fixup::Long(at) => {
+                let index = at - START;
+                self.rom[index] = (address >> 8) as u8;
+                self.rom[index + 1] = address as u8;
+            }
+            Fixup::Unpack(at, nibble) => {
+                let index = at - START;
+                self.rom[index + 1] = match nibble {
+                    Some(nibble) => nibble << 4 | (self.short_address(address)? >> 8) as u8,
+                    None => (address >> 8) as u8,
+                };
+                self.rom[index + 3] = address as u8;
+            }
+        }
+        Ok(())
+    }
+
+    fn short_address(&self, address: usize) -> Result<usize> {
+        if address > 0xFFF {
+            return self.error(f!("The address 0x{:04X} does not fit into 12 bits", address));
+        }
+        Ok(address)
+    }
+
+    fn define(&mut self, name: String, address: usize) -> Result<()> {
+        if self.labels.contains_key(&name) || self.constants.contains_key(&name) {
+            return self.error(f!("The name '{}' is already defined", name));
+        }
+        let (resolved, pending): (Vec<Pending>, Vec<Pending>) =
+            core::mem::take(&mut self.pending).into_iter().partition(|pending| pending.name == name);
+        self.pending = pending;
+        let line = self.line;
+        for pending in resolved {
+            self.line = pending.line;
+            self.patch(pending.fixup, address)?;
+        }
+        self.line = line;
+        self.labels.insert(name, address);
+        Ok(())
+    }
+
+    // Fügt Tokens (aus Makros und String-Modi) an der aktuellen Stelle ein
+    fn insert(&mut self, tokens: Vec<Token>) -> Result<()> {
+        if self.tokens.len() + tokens.len() > MAX_TOKENS {
+            return self.error("Too many macro expansions");
+        }
+        self.tokens.splice(self.position..self.position, tokens);
+        Ok(())
+    }
+
+    // Ersetzt Namen im Rumpf eines Makros, die Zeile wird die des Aufrufs
+    fn substitute(&self, body: &[Token], values: &HashMap<String, String>) -> Vec<Token> {
+        body.iter()
+            .map(|token| Token {
+                text: match values.get(&token.text) {
+                    Some(value) if !token.string => value.clone(),
+                    _ => token.text.clone(),
+                },
+                line: self.line,
+                string: token.string,
+            })
+            .collect()
+    }
+
+    // Tokens bis zur passenden `}`, die öffnende `{` ist schon gelesen
+    fn block(&mut self) -> Result<Vec<Token>> {
+        let mut depth = 0;
+        let mut body = Vec::new();
+        loop {
+            let token = self.next()?;
+            if !token.string && token.text == "{" {
+                depth += 1;
+            } else if !token.string && token.text == "}" {
+                if depth == 0 {
+                    return Ok(body);
+                }
+                depth -= 1;
+            }
+            body.push(token);
+        }
+    }
+
+    fn statement(&mut self) -> Result<()> {
+        let token = self.next()?;
+        if token.string {
+            return self.error(f!("Unexpected string \"{}\"", token.text));
+        }
+        match token.text.as_str() {
+            ":" => {
+                let name = self.name()?;
+                if name == "main" && self.jump_to_main && self.here == START + 2 {
+                    // `main` steht am Anfang, der Sprung entfällt
+                    self.jump_to_main = false;
+                    self.rom.clear();
+                    self.written.clear();
+                    self.here = START;
+                }
+                self.define(name, self.here)?;
+            }
+            ":next" => {
+                let name = self.name()?;
+                self.define(name, self.here + 1)?;
+            }
+            ":const" => {
+                let name = self.name()?;
+                if self.labels.contains_key(&name) || self.constants.contains_key(&name) {
+                    return self.error(f!("The name '{}' is already defined", name));
+                }
+                let text = self.word()?;
+                let value = match self.lookup(&text).or_else(|| self.labels.get(&text).map(|address| *address as i64)) {
+                    Some(value) => value,
+                    None => return self.error(f!("Expected a number, got '{}'", text)),
+                };
+                self.constants.insert(name, value as f64);
+            }
+            ":calc" => {
+                let name = self.name()?;
+                if self.labels.contains_key(&name) {
+                    return self.error(f!("The name '{}' is already defined", name));
+                }
+                let value = self.calc()?;
+                self.constants.insert(name, value);
+            }
+            ":alias" => {
+                let name = self.name()?;
+                let register = if self.peek() == Some("{") {
+                    match self.calc()? as i64 {
+                        register @ 0..=0xF => register as u8,
+                        register => return self.error(f!("v{} is not a register", register)),
+                    }
+                } else {
+                    self.register()?
+                };
+                self.aliases.insert(name, register);
+            }
+            ":org" => {
+                let address = self.number()?;
+                if !(START as i64..=0xFFFF).contains(&address) {
+                    return self.error(f!("The address {} for ':org' is out of range", address));
+                }
+                self.here = address as usize;
+            }
+            ":byte" => {
+                let value = self.byte_value()?;
+                self.byte(value)?;
+            }
+            ":pointer" => {
+                let address = self.address()?;
+                let at = self.here;
+                self.instruction(0x00, 0x00)?;
+                self.reference(address, Fixup::Long(at))?;
+            }
+            ":call" => {
+                let address = self.address()?;
+                self.address_instruction(0x2, address)?;
+            }
+            ":unpack" => {
+                let nibble = if self.peek() == Some("long") {
+                    self.next()?;
+                    None
+                } else {
+                    Some(self.nibble()?)
+                };
+                let address = self.address()?;
+                let at = self.here;
+                self.instruction(0x60 | self.alias("unpack-hi"), 0x00)?;
+                self.instruction(0x60 | self.alias("unpack-lo"), 0x00)?;
+                self.reference(address, Fixup::Unpack(at, nibble))?;
+            }
+            ":assert" => {
+                let message = if self.peek_string() { Some(self.string()?) } else { None };
+                if self.calc()? == 0.0 {
+                    return self.error(match message {
+                        Some(message) => f!("Assertion failed: {}", message),
+                        None => "Assertion failed".into(),
+                    });
+                }
+            }
+            ":macro" => {
+                let name = self.name()?;
+                let mut arguments = Vec::new();
+                loop {
+                    let argument = self.word()?;
+                    if argument == "{" {
+                        break;
+                    }
+                    arguments.push(argument);
+                }
+                let body = self.block()?;
+                self.macros.insert(name, Macro { arguments, body, calls: 0 });
+            }
+            ":stringmode" => {
+                let name = self.word()?;
+                if !self.string_modes.contains_key(&name) && !is_identifier(&name) {
+                    return self.error(f!("'{}' cannot be used as a name", name));
+                }
+                let alphabet = self.string()?;
+                self.expect("{")?;
+                let body = self.block()?;
+                let mode = self.string_modes.entry(name).or_default();
+                for (value, c) in alphabet.chars().enumerate() {
+                    mode.insert(c, (value, body.clone()));
+                }
+            }
+            // Nur für den Debugger von Octo
+            ":breakpoint" | ":proto" => {
+                self.next()?;
+            }
+            ":monitor" => {
+                self.next()?;
+                self.next()?;
+            }
+            ";" | "return" => self.instruction(0x00, 0xEE)?,
+            "clear" => self.instruction(0x00, 0xE0)?,
+            "hires" => self.instruction(0x00, 0xFF)?,
+            "lores" => self.instruction(0x00, 0xFE)?,
+            "exit" => self.instruction(0x00, 0xFD)?,
+            "scroll-left" => self.instruction(0x00, 0xFC)?,
+            "scroll-right" => self.instruction(0x00, 0xFB)?,
+            "scroll-down" => {
+                let lines = self.nibble()?;
+                self.instruction(0x00, 0xC0 | lines)?;
+            }
+            "scroll-up" => {
+                let lines = self.nibble()?;
+                self.instruction(0x00, 0xD0 | lines)?;
+            }
+            "audio" => self.instruction(0xF0, 0x02)?,
+            "plane" => {
+                let planes = self.nibble()?;
+                self.instruction(0xF0 | planes, 0x01)?;
+            }
+            "jump" | "jump0" | "native" => {
+                let address = self.address()?;
+                let prefix = match token.text.as_str() {
+                    "jump" => 0x1,
+                    "jump0" => 0xB,
+                    _ => 0x0,
+                };
+                self.address_instruction(prefix, address)?;
+            }
+            "bcd" => {
+                let x = self.register()?;
+                self.instruction(0xF0 | x, 0x33)?;
+            }
+            "save" | "load" => {
+                let save = token.text == "save";
+                let x = self.register()?;
+                if self.peek() == Some("-") {
+                    self.next()?;
+                    let y = self.register()?;
+                    self.instruction(0x50 | x, y << 4 | if save { 0x2 } else { 0x3 })?;
+                } else {
+                    self.instruction(0xF0 | x, if save { 0x55 } else { 0x65 })?;
+                }
+            }
+            "saveflags" | "loadflags" => {
+                let x = self.register()?;
+                self.instruction(0xF0 | x, if token.text == "saveflags" { 0x75 } else { 0x85 })?;
+            }
+            "sprite" => {
+                let x = self.register()?;
+                let y = self.register()?;
+                let n = self.nibble()?;
+                self.instruction(0xD0 | x, y << 4 | n)?;
+            }
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+                let x = self.register()?;
+                let kk = match token.text.as_str() {
+                    "delay" => 0x15,
+                    "buzzer" => 0x18,
+                    _ => 0x3A,
+                };
+                self.instruction(0xF0 | x, kk)?;
+            }
+            "i" => self.i_statement()?,
+            "loop" => {
+                self.loops.push((self.here, self.line));
+                self.whiles.push(None);
+            }
+            "while" => {
+                if self.loops.is_empty() {
+                    return self.error("This 'while' is not inside a loop");
+                }
+                let condition = self.condition()?;
+                self.skip_unless(&condition, true)?;
+                self.whiles.push(Some(self.here));
+                self.instruction(0x10, 0x00)?;
+            }
+            "again" => {
+                let (start, _) = match self.loops.pop() {
+                    Some(start) => start,
+                    None => return self.error("This 'again' does not have a matching 'loop'"),
+                };
+                self.address_instruction(0x1, Address::Known(start))?;
+                while let Some(Some(at)) = self.whiles.pop() {
+                    self.patch(Fixup::Short(at), self.here)?;
+                }
+            }
+            "if" => {
+                let condition = self.condition()?;
+                match self.word()?.as_str() {
+                    "then" => self.skip_unless(&condition, false)?,
+                    "begin" => {
+                        self.skip_unless(&condition, true)?;
+                        self.branches.push((self.here, self.line));
+                        self.instruction(0x10, 0x00)?;
+                    }
+                    other => return self.error(f!("Expected 'then' or 'begin', got '{}'", other)),
+                }
+            }
+            "else" => {
+                let (at, line) = match self.branches.pop() {
+                    Some(branch) => branch,
+                    None => return self.error("This 'else' does not have a matching 'begin'"),
+                };
+                self.branches.push((self.here, line));
+                self.instruction(0x10, 0x00)?;
+                self.patch(Fixup::Short(at), self.here)?;
+            }
+            "end" => {
+                let (at, _) = match self.branches.pop() {
+                    Some(branch) => branch,
+                    None => return self.error("This 'end' does not have a matching 'begin'"),
+                };
+                self.patch(Fixup::Short(at), self.here)?;
+            }
+            text => {
+                if let Some(x) = self.register_of(text) {
+                    self.register_statement(x)?;
+                } else if self.macros.contains_key(text) {
+                    self.expand_macro(text)?;
+                } else if self.string_modes.contains_key(text) {
+                    self.expand_string(text)?;
+                } else if let Some(value) = self.lookup(text) {
+                    // Zahlen und Konstanten allein sind Daten
+                    let value = self.fit_byte(value)?;
+                    self.byte(value)?;
+                } else if let Some(address) = self.labels.get(text) {
+                    self.address_instruction(0x2, Address::Known(*address))?;
+                } else if is_identifier(text) {
+                    self.address_instruction(0x2, Address::Forward(token.text))?;
+                } else {
+                    return self.error(f!("Unexpected '{}'", text));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn register_statement(&mut self, x: u8) -> Result<()> {
+        let operator = self.word()?;
+        if operator == ":=" {
+            match self.peek() {
+                Some("random") => {
+                    self.next()?;
+                    let mask = self.byte_value()?;
+                    return self.instruction(0xC0 | x, mask);
+                }
+                Some("key") => {
+                    self.next()?;
+                    return self.instruction(0xF0 | x, 0x0A);
+                }
+                Some("delay") => {
+                    self.next()?;
+                    return self.instruction(0xF0 | x, 0x07);
+                }
+                _ => {}
+            }
+        }
+        if self.peek_register() {
+            let y = self.register()?;
+            let n = match operator.as_str() {
+                ":=" => 0x0,
+                "|=" => 0x1,
+                "&=" => 0x2,
+                "^=" => 0x3,
+                "+=" => 0x4,
+                "-=" => 0x5,
+                ">>=" => 0x6,
+                "=-" => 0x7,
+                "<<=" => 0xE,
+                _ => return self.error(f!("Unknown operator '{}'", operator)),
+            };
+            return self.instruction(0x80 | x, y << 4 | n);
+        }
+        let value = self.byte_value()?;
+        match operator.as_str() {
+            ":=" => self.instruction(0x60 | x, value),
+            "+=" => self.instruction(0x70 | x, value),
+            "-=" => self.instruction(0x70 | x, value.wrapping_neg()),
+            _ => self.error(f!("The operator '{}' needs a register", operator)),
+        }
+    }
+
+    fn i_statement(&mut self) -> Result<()> {
+        let operator = self.word()?;
+        match operator.as_str() {
+            ":=" => match self.peek() {
+                Some("long") => {
+                    self.next()?;
+                    let address = self.address()?;
+                    self.instruction(0xF0, 0x00)?;
+                    let at = self.here;
+                    self.instruction(0x00, 0x00)?;
+                    self.reference(address, Fixup::Long(at))
+                }
+                Some("hex") | Some("bighex") => {
+                    let big = self.word()? == "bighex";
+                    let x = self.register()?;
+                    self.instruction(0xF0 | x, if big { 0x30 } else { 0x29 })
+                }
+                _ => {
+                    let address = self.address()?;
+                    self.address_instruction(0xA, address)
+                }
+            },
+            "+=" => {
+                let x = self.register()?;
+                self.instruction(0xF0 | x, 0x1E)
+            }
+            _ => self.error(f!("Unknown operator '{}' for i", operator)),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition> {
+        let x = self.register()?;
+        let comparison = self.word()?;
+        let operand = match comparison.as_str() {
+            "key" | "-key" => None,
+            "==" | "!=" | "<" | ">" | "<=" | ">=" if self.peek_register() => Some(Operand::Register(self.register()?)),
+            "==" | "!=" | "<" | ">" | "<=" | ">=" => Some(Operand::Value(self.byte_value()?)),
+            _ => return self.error(f!("Expected a comparison, got '{}'", comparison)),
+        };
+        Ok(Condition { x, comparison, operand })
+    }
+
+    // Überspringt die nächste Instruktion, wenn die Bedingung nicht gilt (mit `negated`: wenn
+    // sie gilt). <, >, <= und >= vergleichen über vF (`compare-temp`)
+    fn skip_unless(&mut self, condition: &Condition, negated: bool) -> Result<()> {
+        let comparison = match (condition.comparison.as_str(), negated) {
+            ("==", true) => "!=",
+            ("!=", true) => "==",
+            ("key", true) => "-key",
+            ("-key", true) => "key",
+            ("<", true) => ">=",
+            (">", true) => "<=",
+            ("<=", true) => ">",
+            (">=", true) => "<",
+            (comparison, _) => comparison,
+        };
+        let x = condition.x;
+        match (comparison, condition.operand) {
+            ("key", _) => self.instruction(0xE0 | x, 0xA1),
+            ("-key", _) => self.instruction(0xE0 | x, 0x9E),
+            ("==", Some(Operand::Register(y))) => self.instruction(0x90 | x, y << 4),
+            ("==", Some(Operand::Value(value))) => self.instruction(0x40 | x, value),
+            ("!=", Some(Operand::Register(y))) => self.instruction(0x50 | x, y << 4),
+            ("!=", Some(Operand::Value(value))) => self.instruction(0x30 | x, value),
+            (_, Some(operand)) => {
+                let temp = self.alias("compare-temp");
+                match operand {
+                    Operand::Register(y) => self.instruction(0x80 | temp, y << 4)?,
+                    Operand::Value(value) => self.instruction(0x60 | temp, value)?,
+                }
+                let (subtract, skip) = match comparison {
+                    ">" => (0x5, 0x3F),
+                    "<" => (0x7, 0x3F),
+                    ">=" => (0x7, 0x4F),
+                    _ => (0x5, 0x4F),
+                };
+                self.instruction(0x80 | temp, x << 4 | subtract)?;
+                self.instruction(skip, 0x01)
+            }
+            (_, None) => self.error(f!("Expected a comparison, got '{}'", comparison)),
+        }
+    }
+
+    fn expand_macro(&mut self, name: &str) -> Result<()> {
+        let Macro { arguments, body, calls } = match self.macros.get(name) {
+            Some(definition) => definition.clone(),
+            None => return self.error(f!("Unknown macro '{}'", name)),
+        };
+        let mut values = HashMap::new();
+        for argument in arguments {
+            let value = self.next()?;
+            values.insert(argument, value.text);
+        }
+        values.insert("CALLS".into(), calls.to_string());
+        if let Some(definition) = self.macros.get_mut(name) {
+            definition.calls += 1;
+        }
+        let tokens = self.substitute(&body, &values);
+        self.insert(tokens)
+    }
+
+    fn expand_string(&mut self, name: &str) -> Result<()> {
+        let text = self.string()?;
+        let mut tokens = Vec::new();
+        for (index, c) in text.chars().enumerate() {
+            let (value, body) = match self.string_modes.get(name).and_then(|mode| mode.get(&c)) {
+                Some(entry) => entry,
+                None => return self.error(f!("The string mode '{}' does not include {:?}", name, c)),
+            };
+            let values = [("VALUE", *value), ("CHAR", c as usize), ("INDEX", index)]
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            tokens.extend(self.substitute(body, &values));
+        }
+        self.insert(tokens)
+    }
+
+    fn calc(&mut self) -> Result<f64> {
+        self.expect("{")?;
+        let value = self.expression()?;
+        self.expect("}")?;
+        Ok(value)
+    }
+
+    // Wie in Octo ohne Vorrangregeln von rechts nach links ausgewertet: `2 * 3 + 1` ist 8
+    fn expression(&mut self) -> Result<f64> {
+        let left = self.term()?;
+        if matches!(self.peek(), Some(")") | Some("}")) {
+            return Ok(left);
+        }
+        let operator = self.word()?;
+        let right = self.expression()?;
+        let (a, b) = (left as i64, right as i64);
+        Ok(match operator.as_str() {
+            "+" => left + right,
+            "-" => left - right,
+            "*" => left * right,
+            "/" => left / right,
+            "%" => left % right,
+            "&" => (a & b) as f64,
+            "|" => (a | b) as f64,
+            "^" => (a ^ b) as f64,
+            "<<" => a.wrapping_shl(b as u32) as f64,
+            ">>" => a.wrapping_shr(b as u32) as f64,
+            "pow" => left.powf(right),
+            "min" => left.min(right),
+            "max" => left.max(right),
+            "<" => f64::from(u8::from(left < right)),
+            ">" => f64::from(u8::from(left > right)),
+            "<=" => f64::from(u8::from(left <= right)),
+            ">=" => f64::from(u8::from(left >= right)),
+            "==" => f64::from(u8::from(left == right)),
+            "!=" => f64::from(u8::from(left != right)),
+            _ => return self.error(f!("Unknown operator '{}'", operator)),
+        })
+    }
+
+    fn term(&mut self) -> Result<f64> {
+        let text = self.word()?;
+        Ok(match text.as_str() {
+            "(" => {
+                let value = self.expression()?;
+                self.expect(")")?;
+                value
+            }
+            "-" => -self.term()?,
+            "~" => !(self.term()? as i64) as f64,
+            "!" => f64::from(u8::from(self.term()? == 0.0)),
+            "sin" => self.term()?.sin(),
+            "cos" => self.term()?.cos(),
+            "tan" => self.term()?.tan(),
+            "exp" => self.term()?.exp(),
+            "log" => self.term()?.ln(),
+            "abs" => self.term()?.abs(),
+            "sqrt" => self.term()?.sqrt(),
+            "sign" => {
+                let value = self.term()?;
+                if value == 0.0 {
+                    0.0
+                } else {
+                    value.signum()
+                }
+            }
+            "ceil" => self.term()?.ceil(),
+            "floor" => self.term()?.floor(),
+            // Bereits übersetztes Byte an einer Adresse
+            "@" => {
+                let address = self.term()? as i64 as usize;
+                let byte = address.checked_sub(START).and_then(|index| self.rom.get(index));
+                f64::from(byte.copied().unwrap_or_default())
+            }
+            "HERE" => self.here as f64,
+            "PI" => core::f64::consts::PI,
+            "E" => core::f64::consts::E,
+            _ => match parse_number(&text) {
+                Some(value) => value as f64,
+                None => match (self.constants.get(&text), self.labels.get(&text)) {
+                    (Some(value), _) => *value,
+                    (None, Some(address)) => *address as f64,
+                    (None, None) => return self.error(f!("Undefined name '{}'", text)),
+                },
+            },
+        })
+    }
+}
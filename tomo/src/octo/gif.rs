@@ -0,0 +1,191 @@
+// Minimaler GIF-Decoder: Liefert nur die Farbindizes der einzelnen Frames,
+// Farbtabellen, Positionen und Erweiterungen werden übersprungen
+
+use super::CartridgeError;
+
+// Größte Code-Länge des LZW-Verfahrens in GIFs
+const MAX_CODE_SIZE: u8 = 12;
+// Größtes Frame, das dekodiert wird. Breite und Höhe stammen aus der Datei und
+// könnten sonst riesige Speichermengen anfordern
+const MAX_PIXELS: usize = 1 << 24;
+
+// Ein Frame mit den Farbindizes Zeile für Zeile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub indices: Vec<u8>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, CartridgeError> {
+        let byte = *self.data.get(self.position).ok_or(CartridgeError::Gif("unexpected end of file"))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn word(&mut self) -> Result<u16, CartridgeError> {
+        Ok(u16::from(self.byte()?) | u16::from(self.byte()?) << 8)
+    }
+
+    fn skip(&mut self, length: usize) -> Result<(), CartridgeError> {
+        if self.position + length > self.data.len() {
+            return Err(CartridgeError::Gif("unexpected end of file"));
+        }
+        self.position += length;
+        Ok(())
+    }
+
+    // Liest eine Folge von Unterblöcken (Länge + Daten) bis zum leeren Block
+    fn sub_blocks(&mut self) -> Result<Vec<u8>, CartridgeError> {
+        let mut blocks = Vec::new();
+        loop {
+            let length = self.byte()? as usize;
+            if length == 0 {
+                return Ok(blocks);
+            }
+            let start = self.position;
+            self.skip(length)?;
+            blocks.extend_from_slice(&self.data[start..start + length]);
+        }
+    }
+}
+
+// Dekodiert alle Frames eines GIFs in der gespeicherten Reihenfolge
+pub fn decode_frames(data: &[u8]) -> Result<Vec<Frame>, CartridgeError> {
+    if !data.starts_with(b"GIF87a") && !data.starts_with(b"GIF89a") {
+        return Err(CartridgeError::Gif("missing GIF header"));
+    }
+    let mut reader = Reader { data, position: 6 };
+
+    // Logical Screen Descriptor
+    reader.skip(4)?;
+    let flags = reader.byte()?;
+    reader.skip(2)?;
+    if flags & 0x80 != 0 {
+        reader.skip(3 << ((flags & 0x07) + 1))?;
+    }
+
+    let mut frames = Vec::new();
+    loop {
+        match reader.byte()? {
+            // Erweiterungen (Animation, Kommentare, ...)
+            0x21 => {
+                reader.byte()?;
+                reader.sub_blocks()?;
+            }
+            // Image Descriptor
+            0x2C => {
+                reader.skip(4)?;
+                let width = reader.word()? as usize;
+                let height = reader.word()? as usize;
+                let flags = reader.byte()?;
+                if flags & 0x80 != 0 {
+                    reader.skip(3 << ((flags & 0x07) + 1))?;
+                }
+                if width * height > MAX_PIXELS {
+                    return Err(CartridgeError::Gif("frame too large"));
+                }
+                let min_code_size = reader.byte()?;
+                let indices = decode_lzw(min_code_size, &reader.sub_blocks()?, width * height)?;
+                let indices = if flags & 0x40 != 0 { deinterlace(&indices, width, height) } else { indices };
+                frames.push(Frame { width, height, indices });
+            }
+            // Trailer
+            0x3B => return Ok(frames),
+            _ => return Err(CartridgeError::Gif("unknown block")),
+        }
+    }
+}
+
+// LZW-Dekompression mit variabler Code-Länge (niederwertigste Bits zuerst)
+fn decode_lzw(min_code_size: u8, data: &[u8], length: usize) -> Result<Vec<u8>, CartridgeError> {
+    if !(1..MAX_CODE_SIZE).contains(&min_code_size) {
+        return Err(CartridgeError::Gif("invalid LZW code size"));
+    }
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    let mut table: Vec<Vec<u8>> = (0..clear).map(|index| vec![index as u8]).collect();
+    table.push(Vec::new());
+    table.push(Vec::new());
+
+    let mut code_size = min_code_size + 1;
+    let mut previous: Option<usize> = None;
+    // Wächst mit den Daten, statt `length` im Voraus zu reservieren
+    let mut output = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0u8;
+    let mut bytes = data.iter();
+
+    while output.len() < length {
+        while bits < code_size {
+            match bytes.next() {
+                Some(byte) => {
+                    buffer |= u32::from(*byte) << bits;
+                    bits += 8;
+                }
+                None => return Ok(output),
+            }
+        }
+        let code = (buffer & ((1 << code_size) - 1)) as usize;
+        buffer >>= code_size;
+        bits -= code_size;
+
+        if code == clear {
+            table.truncate(end + 1);
+            code_size = min_code_size + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+
+        let entry = match previous {
+            _ if code < table.len() => table[code].clone(),
+            // Sonderfall: Der Code wird gerade erst angelegt
+            Some(previous) if code == table.len() => {
+                let mut entry = table[previous].clone();
+                entry.push(entry[0]);
+                entry
+            }
+            _ => return Err(CartridgeError::Gif("invalid LZW code")),
+        };
+        output.extend_from_slice(&entry);
+
+        if let Some(previous) = previous {
+            if table.len() < 1 << MAX_CODE_SIZE {
+                let mut next = table[previous].clone();
+                next.push(entry[0]);
+                table.push(next);
+                if table.len() == 1 << code_size && code_size < MAX_CODE_SIZE {
+                    code_size += 1;
+                }
+            }
+        }
+        previous = Some(code);
+    }
+
+    output.truncate(length);
+    Ok(output)
+}
+
+// Bringt die Zeilen eines "interlaced" Frames in die normale Reihenfolge
+fn deinterlace(indices: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut output = vec![0; (width * height).min(indices.len())];
+    let passes = [(0, 8), (4, 8), (2, 4), (1, 2)];
+    let rows = passes.iter().flat_map(|&(start, step)| (start..height).step_by(step));
+    for (source, target) in rows.enumerate() {
+        let source = &indices[(source * width).min(indices.len())..((source + 1) * width).min(indices.len())];
+        // Zeilen, die in gekürzten Daten fehlen, bleiben weg
+        if let Some(row) = output.get_mut(target * width..target * width + source.len()) {
+            row.copy_from_slice(source);
+        }
+    }
+    output
+}
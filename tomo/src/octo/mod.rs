@@ -0,0 +1,80 @@
+//! Unterstützung für Octo (https://github.com/JohnEarnest/Octo)
+//!
+//! Octo-Spiele werden oft als "Cartridge" geteilt: Ein GIF, in dessen Pixeln Programm und
+//! Optionen stecken. Die unteren 2 Bit jedes Farbindex ergeben (über alle Frames hinweg,
+//! höchstwertige Bits zuerst) einen Datenstrom, welcher mit der Länge als 32-Bit Big-Endian
+//! beginnt, gefolgt von UTF-8 JSON der Form `{ "program": "...", "options": { ... } }`.
+//!
+//! Das Programm liegt als Octo-Quelltext vor und wird mit `compile` in Bytes übersetzt.
+//!
+//! `OctoOptions` entspricht dem JSON-Objekt, mit dem Octo und verwandte Werkzeuge
+//! die Einstellungen einer ROM veröffentlichen, und kann gelesen und geschrieben werden.
+
+mod cartridge;
+mod compiler;
+mod gif;
+mod options;
+
+use std::fmt;
+
+use crate::chip8::machine::LoadError;
+use crate::prelude::*;
+
+pub use cartridge::Cartridge;
+pub use compiler::{compile, CompileError};
+pub use options::{OctoOptions, OptionsError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CartridgeError {
+    // Das Bild ist kein gültiges GIF
+    Gif(&'static str),
+    // Die Bilddaten enthalten keine gültigen Nutzdaten
+    Payload(&'static str),
+    // Die Nutzdaten sind kein gültiges JSON
+    Json(String),
+    // Der Quelltext lässt sich nicht übersetzen
+    Source(CompileError),
+    // Die Optionen lassen sich nicht umsetzen
+    Options(OptionsError),
+    // Das Programm lässt sich nicht laden
+    Load(LoadError),
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartridgeError::Gif(message) => write!(f, "Invalid GIF: {}", message),
+            CartridgeError::Payload(message) => write!(f, "Invalid cartridge data: {}", message),
+            CartridgeError::Json(message) => write!(f, "Invalid cartridge JSON: {}", message),
+            CartridgeError::Source(error) => write!(f, "Invalid Octo source: {}", error),
+            CartridgeError::Options(error) => write!(f, "{}", error),
+            CartridgeError::Load(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {}
+
+impl From<CompileError> for CartridgeError {
+    fn from(error: CompileError) -> Self {
+        CartridgeError::Source(error)
+    }
+}
+
+impl From<OptionsError> for CartridgeError {
+    fn from(error: OptionsError) -> Self {
+        CartridgeError::Options(error)
+    }
+}
+
+impl From<LoadError> for CartridgeError {
+    fn from(error: LoadError) -> Self {
+        CartridgeError::Load(error)
+    }
+}
+
+impl From<CartridgeError> for JsValue {
+    fn from(error: CartridgeError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
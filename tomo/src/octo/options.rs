@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chip8::quirks::Quirks;
+use crate::chip8::Colors;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionsError(pub String);
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid Octo options: {}", self.0)
+    }
+}
+
+impl std::error::Error for OptionsError {}
+
+impl From<OptionsError> for JsValue {
+    fn from(error: OptionsError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+// Optionen, mit denen Octo eine ROM ausführt, im JSON-Format von Octo.
+// Fehlende Einträge erhalten die Standardwerte von Octo, unbekannte Einträge
+// bleiben beim Exportieren erhalten
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OctoOptions {
+    // Instruktionen pro Frame
+    pub tickrate: u16,
+    // Farben als "#RRGGBB": Pixel der ersten bzw. zweiten Ebene (XO-CHIP),
+    // Pixel beider Ebenen, Hintergrund und Rahmen mit bzw. ohne Ton
+    pub fill_color: String,
+    pub fill_color2: String,
+    pub blend_color: String,
+    pub background_color: String,
+    pub buzz_color: String,
+    pub quiet_color: String,
+    // Quirks, benannt wie in Octo
+    pub shift_quirks: bool,
+    pub load_store_quirks: bool,
+    pub vf_order_quirks: bool,
+    pub clip_quirks: bool,
+    pub jump_quirks: bool,
+    pub v_blank_quirks: bool,
+    pub logic_quirks: bool,
+    // XO-CHIP Befehle erlauben (wird von Tomo nicht unterstützt, siehe `validate`)
+    #[serde(rename = "enableXO")]
+    pub enable_xo: bool,
+    // Drehung der Anzeige im Uhrzeigersinn (0, 90, 180 oder 270 Grad)
+    pub screen_rotation: u16,
+    // Größte erlaubte ROM in Bytes
+    pub max_size: u32,
+    pub touch_input_mode: String,
+    pub font_style: String,
+    // Weitere Einträge, die Tomo nicht kennt
+    #[wasm_bindgen(skip)]
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+#[wasm_bindgen]
+impl OctoOptions {
+    // Standardwerte von Octo
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        OctoOptions {
+            tickrate: 20,
+            fill_color: "#FFCC00".to_string(),
+            fill_color2: "#FF6600".to_string(),
+            blend_color: "#662200".to_string(),
+            background_color: "#996600".to_string(),
+            buzz_color: "#FFAA00".to_string(),
+            quiet_color: "#000000".to_string(),
+            shift_quirks: false,
+            load_store_quirks: false,
+            vf_order_quirks: false,
+            clip_quirks: false,
+            jump_quirks: false,
+            v_blank_quirks: false,
+            logic_quirks: false,
+            enable_xo: false,
+            screen_rotation: 0,
+            max_size: 3584,
+            touch_input_mode: "none".to_string(),
+            font_style: "octo".to_string(),
+            extra: BTreeMap::new(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<OctoOptions, OptionsError> {
+        serde_json::from_str(json).map_err(|error| OptionsError(error.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Octo options are always serializable")
+    }
+
+    // Prüft, ob Tomo die Optionen umsetzen kann: XO-CHIP wird nicht unterstützt, gedreht
+    // wird nur in Schritten von 90 Grad
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        if self.enable_xo {
+            return Err(OptionsError("XO-CHIP is not supported".to_string()));
+        }
+        if !matches!(self.screen_rotation, 0 | 90 | 180 | 270) {
+            return Err(OptionsError(f!("A screen rotation of {} degrees is not supported", self.screen_rotation)));
+        }
+        Ok(())
+    }
+
+    // Octo geht von einem modernen Interpreter aus und schaltet nur einzelne Quirks an
+    pub fn quirks(&self) -> Quirks {
+        Quirks {
+            shift: self.shift_quirks,
+            memory_leave_i_unchanged: self.load_store_quirks,
+            wrap: !self.clip_quirks,
+            jump: self.jump_quirks,
+            vblank: self.v_blank_quirks,
+            logic: self.logic_quirks,
+            vf_order: self.vf_order_quirks,
+            ..Quirks::modern()
+        }
+    }
+
+    // Übernimmt Quirks (`memory_increment_by_x` gibt es in Octo nicht)
+    pub fn set_quirks(&mut self, quirks: &Quirks) {
+        self.shift_quirks = quirks.shift;
+        self.load_store_quirks = quirks.memory_leave_i_unchanged;
+        self.clip_quirks = !quirks.wrap;
+        self.jump_quirks = quirks.jump;
+        self.v_blank_quirks = quirks.vblank;
+        self.logic_quirks = quirks.logic;
+        self.vf_order_quirks = quirks.vf_order;
+    }
+
+    // Farben für die Darstellung, ungültige Angaben behalten die Standardfarben
+    pub fn colors(&self) -> Colors {
+        let default = Colors::default();
+        let parse = |color: &str, default: u32| Colors::parse_hex(color).unwrap_or(default);
+        Colors {
+            background: parse(&self.background_color, default.background),
+            foreground: parse(&self.fill_color, default.foreground),
+            buzzer: parse(&self.buzz_color, default.buzzer),
+            silence: parse(&self.quiet_color, default.silence),
+        }
+    }
+
+    pub fn set_colors(&mut self, colors: &Colors) {
+        self.background_color = Colors::to_hex(colors.background);
+        self.fill_color = Colors::to_hex(colors.foreground);
+        self.buzz_color = Colors::to_hex(colors.buzzer);
+        self.quiet_color = Colors::to_hex(colors.silence);
+    }
+}
+
+impl Default for OctoOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,235 @@
+//! Host-Schnittstelle des Emulators
+//!
+//! Alles, was der Emulator vom Host braucht (Zufall, Diagnose-Ausgaben, Ton und Zeit),
+//! läuft über den `Platform`-Trait, welcher in den `Processor` injiziert wird.
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, rc::Rc, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
+
+use log::Level;
+
+use crate::prelude::*;
+
+pub trait Platform {
+    // Liefert ein zufälliges Byte für RND
+    fn random_byte(&mut self) -> u8;
+
+    // Setzt den Startwert des Zufallsgenerators neu
+    fn seed(&mut self, seed: u32);
+
+    // Diagnose-Ausgaben mit Level und Ziel (siehe `reporting::target`), standardmäßig
+    // an die `log`-Fassade weitergegeben, wo sie gefiltert werden können
+    fn log(&mut self, level: Level, target: &str, message: fmt::Arguments) {
+        log::log!(target: target, level, "{}", message);
+    }
+
+    // Wird aufgerufen, wenn der Piep-Ton an- bzw. ausgeschaltet werden soll
+    fn audio(&mut self, _on: bool) {}
+
+    // Monotone Uhr in Mikrosekunden
+    fn now_micros(&self) -> u64;
+}
+
+// Mit Heap wird die Plattform als Trait-Objekt gehalten und kann ausgetauscht werden,
+// ohne Heap wird immer die `BarePlatform` genutzt
+#[cfg(feature = "alloc")]
+pub type PlatformHandle = Box<dyn Platform>;
+#[cfg(not(feature = "alloc"))]
+pub type PlatformHandle = BarePlatform;
+
+// Standard-Plattform je nach Kompilationsziel
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+pub type DefaultPlatform = WasmPlatform;
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub type DefaultPlatform = NativePlatform;
+#[cfg(not(feature = "std"))]
+pub type DefaultPlatform = BarePlatform;
+
+#[cfg(feature = "alloc")]
+pub fn default_platform() -> PlatformHandle {
+    Box::new(DefaultPlatform::default())
+}
+
+#[cfg(not(feature = "alloc"))]
+pub fn default_platform() -> PlatformHandle {
+    BarePlatform::default()
+}
+
+// Plattform ohne Host (z.B. Mikrocontroller): Xorshift als Zufall
+// und eine Uhr, die still steht
+#[derive(Clone, Copy, Debug)]
+pub struct BarePlatform {
+    rng: XorShift,
+}
+
+impl Default for BarePlatform {
+    fn default() -> Self {
+        BarePlatform {
+            rng: XorShift::new(random_seed()),
+        }
+    }
+}
+
+impl Platform for BarePlatform {
+    fn random_byte(&mut self) -> u8 {
+        self.rng.next_byte()
+    }
+
+    fn seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+
+    fn now_micros(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+// Browser bzw. Node: `performance.now()` als Uhr (Ausgaben über `reporting::ConsoleLogger`)
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug)]
+pub struct WasmPlatform {
+    rng: XorShift,
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+impl Default for WasmPlatform {
+    fn default() -> Self {
+        WasmPlatform {
+            rng: XorShift::new(random_seed()),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+impl Platform for WasmPlatform {
+    fn random_byte(&mut self) -> u8 {
+        self.rng.next_byte()
+    }
+
+    fn seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+
+    fn now_micros(&self) -> u64 {
+        (performance_now() * 1000.0) as u64
+    }
+}
+
+// Native Ziele: `Instant` als Uhr
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+#[derive(Clone, Copy, Debug)]
+pub struct NativePlatform {
+    rng: XorShift,
+    start: std::time::Instant,
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+impl Default for NativePlatform {
+    fn default() -> Self {
+        NativePlatform {
+            rng: XorShift::new(random_seed()),
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+impl Platform for NativePlatform {
+    fn random_byte(&mut self) -> u8 {
+        self.rng.next_byte()
+    }
+
+    fn seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+
+    fn now_micros(&self) -> u64 {
+        self.start.elapsed().as_micros() as u64
+    }
+}
+
+// Aufgezeichnete Diagnose-Ausgabe
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+// Aufgezeichnete Ereignisse der `TestPlatform`
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct TestRecord {
+    pub logs: Vec<LogEntry>,
+    pub audio: Vec<bool>,
+    // Aktuelle Zeit der Uhr, wird nur über `TestPlatform::advance` bewegt
+    pub now_micros: u64,
+}
+
+// Deterministische Plattform für Tests: fester Startwert, manuelle Uhr und
+// Aufzeichnung aller Ausgaben, die auch nach dem Injizieren lesbar bleibt
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct TestPlatform {
+    rng: XorShift,
+    record: Rc<RefCell<TestRecord>>,
+}
+
+#[cfg(feature = "alloc")]
+impl TestPlatform {
+    pub fn new(seed: u32) -> Self {
+        TestPlatform {
+            rng: XorShift::new(seed),
+            record: Rc::new(RefCell::new(TestRecord::default())),
+        }
+    }
+
+    // Geteilter Zugriff auf die Aufzeichnung
+    pub fn record(&self) -> Rc<RefCell<TestRecord>> {
+        Rc::clone(&self.record)
+    }
+
+    // Stellt die Uhr um die gegebene Zeit vor
+    pub fn advance(&self, micros: u64) {
+        self.record.borrow_mut().now_micros += micros;
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Platform for TestPlatform {
+    fn random_byte(&mut self) -> u8 {
+        self.rng.next_byte()
+    }
+
+    fn seed(&mut self, seed: u32) {
+        self.rng = XorShift::new(seed);
+    }
+
+    fn log(&mut self, level: Level, target: &str, message: fmt::Arguments) {
+        self.record.borrow_mut().logs.push(LogEntry {
+            level,
+            target: String::from(target),
+            message: f!("{}", message),
+        });
+    }
+
+    fn audio(&mut self, on: bool) {
+        self.record.borrow_mut().audio.push(on);
+    }
+
+    fn now_micros(&self) -> u64 {
+        self.record.borrow().now_micros
+    }
+}
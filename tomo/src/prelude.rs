@@ -1,33 +1,68 @@
 //! Crate prelude
 
 // Formatierer als "f"
-pub use std::format as f;
+#[cfg(feature = "alloc")]
+pub use alloc::format as f;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 use js_sys::Math;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
 use rand::{Rng, thread_rng};
 // Wasm_bindgen
 pub use wasm_bindgen::prelude::*;
 
-// Funktion, um einen zufälligen byte zu bekommen | Funktion wird je nack Kompilationsziel verwendet
-#[cfg(target_arch = "wasm32")]
-pub fn random_byte() -> u8 {
-    (Math::random() * 255.0) as u8
+// Funktion, um einen zufälligen Startwert zu bekommen | Funktion wird je nach Kompilationsziel verwendet
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+pub fn random_seed() -> u32 {
+    (Math::random() * u32::MAX as f64) as u32
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn random_byte() -> u8 {
-    let mut rng = thread_rng();
-    (rng.gen::<f32>() * 255.0) as u8
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+pub fn random_seed() -> u32 {
+    thread_rng().gen()
 }
 
+// Ohne Standardbibliothek gibt es keine Entropiequelle, der Startwert
+// kann aber über `Processor::seed_random` gesetzt werden
+#[cfg(not(feature = "std"))]
+pub fn random_seed() -> u32 {
+    XorShift::DEFAULT_SEED
+}
+
+// Xorshift-Zufallsgenerator, der ohne Standardbibliothek auskommt
+#[derive(Clone, Copy, Debug)]
+pub struct XorShift {
+    state: u32,
+}
+
+impl XorShift {
+    pub const DEFAULT_SEED: u32 = 0x2545_F491;
+
+    pub const fn new(seed: u32) -> Self {
+        // Ein Zustand von 0 würde für immer 0 bleiben
+        XorShift {
+            state: if seed == 0 { Self::DEFAULT_SEED } else { seed },
+        }
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        (x >> 24) as u8
+    }
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 #[wasm_bindgen]
 extern {
     #[wasm_bindgen(js_namespace = console)]
     pub fn log(msg: &str);
 }
 
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
 #[wasm_bindgen]
 extern {
     #[wasm_bindgen(js_namespace = console)]
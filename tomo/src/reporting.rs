@@ -1,23 +1,144 @@
-#[cfg(target_arch = "wasm32")]
+//! Diagnose-Ausgaben des Emulators
+//!
+//! Alle Ausgaben haben ein Level und ein Ziel (Subsystem) und laufen über die Plattform
+//! (siehe `platform::Platform::log`), welche sie standardmäßig an die `log`-Fassade weitergibt.
+//! Mit dem Feature "release-logging-off" werden sie aus Release-Builds komplett entfernt.
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::prelude::*;
+
+// Ziele der einzelnen Subsysteme, nach denen gefiltert werden kann
+pub mod target {
+    pub const LOADER: &str = "tomo::loader";
+    pub const CPU: &str = "tomo::cpu";
+    pub const DISPLAY: &str = "tomo::display";
+    pub const INPUT: &str = "tomo::input";
+}
+
+// Gibt es einen Aufruf an die Plattform weiter, wenn das Level nicht zur Kompilierzeit
+// abgeschaltet ist. Die Laufzeit-Filterung übernimmt die Plattform bzw. der Logger
+#[macro_export]
+macro_rules! report {
+    ($platform:expr, $level:expr, $target:expr, $($t:tt)*) => ({
+        let level = $level;
+        if level <= ::log::STATIC_MAX_LEVEL {
+            $platform.log(level, $target, format_args!($($t)*));
+        }
+    })
+}
+
 #[macro_export]
-macro_rules! log {
-    ($($t:tt)*) => (log(&format!($($t)*)))
+macro_rules! error {
+    ($platform:expr, $target:expr, $($t:tt)*) => ($crate::report!($platform, ::log::Level::Error, $target, $($t)*))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 #[macro_export]
-macro_rules! log {
-    ($($t:tt)*) => (println!($($t)*))
+macro_rules! warn {
+    ($platform:expr, $target:expr, $($t:tt)*) => ($crate::report!($platform, ::log::Level::Warn, $target, $($t)*))
 }
 
-#[cfg(target_arch = "wasm32")]
 #[macro_export]
-macro_rules! err {
-    ($($t:tt)*) => (error(&format!($($t)*)))
+macro_rules! info {
+    ($platform:expr, $target:expr, $($t:tt)*) => ($crate::report!($platform, ::log::Level::Info, $target, $($t)*))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 #[macro_export]
-macro_rules! err {
-    ($($t:tt)*) => (eprintln!($($t)*))
+macro_rules! debug {
+    ($platform:expr, $target:expr, $($t:tt)*) => ($crate::report!($platform, ::log::Level::Debug, $target, $($t)*))
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($platform:expr, $target:expr, $($t:tt)*) => ($crate::report!($platform, ::log::Level::Trace, $target, $($t)*))
+}
+
+// Level als Klassen-Repräsentation für JS
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn console_warn(msg: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = debug)]
+    fn console_debug(msg: &str);
+}
+
+// Logger für die Konsole: Im Browser `console.*`, nativ die Standard-Ausgaben
+pub struct ConsoleLogger;
+
+static CONSOLE_LOGGER: ConsoleLogger = ConsoleLogger;
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    #[cfg(all(feature = "std", target_arch = "wasm32"))]
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let msg = f!("[{}] {}: {}", record.level(), record.target(), record.args());
+        match record.level() {
+            log::Level::Error => error(&msg),
+            log::Level::Warn => console_warn(&msg),
+            log::Level::Info => log(&msg),
+            log::Level::Debug | log::Level::Trace => console_debug(&msg),
+        }
+    }
+
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            log::Level::Error | log::Level::Warn => eprintln!("[{}] {}: {}", record.level(), record.target(), record.args()),
+            _ => println!("[{}] {}: {}", record.level(), record.target(), record.args()),
+        }
+    }
+
+    // Ohne Standardbibliothek gibt es keine Konsole
+    #[cfg(not(feature = "std"))]
+    fn log(&self, _record: &Record) {}
+
+    fn flush(&self) {}
+}
+
+// Installiert den Konsolen-Logger mit dem gegebenen Level. Ist bereits ein Logger
+// gesetzt, wird nur das Level angepasst
+#[wasm_bindgen]
+pub fn init_logging(level: LogLevel) {
+    let _ = log::set_logger(&CONSOLE_LOGGER);
+    log::set_max_level(level.into());
+}
+
+// Passt das Level zur Laufzeit an (`LogLevel::Off` schaltet alles ab)
+#[wasm_bindgen]
+pub fn set_log_level(level: LogLevel) {
+    log::set_max_level(level.into());
 }
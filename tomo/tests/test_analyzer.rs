@@ -0,0 +1,110 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::analyzer::{InstructionSet, RomAnalysis};
+use tomo::chip8::machine::{MachineConfig, Variant};
+use tomo::chip8::processor::Processor;
+use tomo::chip8::quirks::Quirks;
+
+#[wasm_bindgen_test]
+fn test_reachable_code() {
+    let analysis = RomAnalysis::analyze(&[
+        0x30, 0x00, // 0x200: SE V0, 0
+        0x12, 0x08, // 0x202: JP 0x208
+        0x22, 0x0C, // 0x204: CALL 0x20C
+        0x12, 0x06, // 0x206: JP 0x206
+        0xB3, 0x00, // 0x208: JP V0, 0x300
+        0xFF, 0xFF, // 0x20A: Daten, nie erreicht
+        0x00, 0xEE, // 0x20C: RET
+    ], 0x200);
+    assert_eq!(analysis.instruction_set, InstructionSet::Chip8);
+    assert_eq!(analysis.confidence, 1.0);
+    assert_eq!(analysis.reachable, 6);
+    assert_eq!(analysis.dynamic_jumps, 1);
+    assert!(!analysis.machine_code);
+
+    // Ein Opcode, den kein Befehlssatz kennt, senkt die Sicherheit
+    let analysis = RomAnalysis::analyze(&[0x60, 0x01, 0x80, 0x18, 0x12, 0x04], 0x200);
+    assert_eq!(analysis.unknown, 1);
+    assert!(analysis.confidence < 0.7);
+}
+
+#[wasm_bindgen_test]
+fn test_instruction_sets() {
+    let detect = |rom: &[u8]| RomAnalysis::analyze(rom, 0x200).instruction_set;
+    assert_eq!(detect(&[0x00, 0xFF, 0x00, 0xFD]), InstructionSet::Schip);
+    // F000 NNNN ist zwei Worte lang, 5XY2 gibt es nur bei XO-CHIP
+    assert_eq!(detect(&[0xF0, 0x00, 0x12, 0x34, 0x50, 0x12, 0x12, 0x06]), InstructionSet::XoChip);
+    assert_eq!(detect(&[0x02, 0xA0, 0xB0, 0x11, 0x12, 0x04]), InstructionSet::Chip8x);
+    assert_eq!(detect(&[0x00, 0x11, 0x01, 0x01, 0x00, 0x00, 0x12, 0x06]), InstructionSet::MegaChip);
+
+    let mut rom = vec![0; 0xC4];
+    rom[..2].copy_from_slice(&[0x12, 0x60]);
+    rom[0xC0..].copy_from_slice(&[0x02, 0x30, 0x12, 0xC2]);
+    let analysis = RomAnalysis::analyze(&rom, 0x200);
+    assert_eq!(analysis.instruction_set, InstructionSet::Hires);
+    assert_eq!(analysis.variant(), Variant::Hires);
+    assert_eq!(analysis.reachable, 2);
+}
+
+#[wasm_bindgen_test]
+fn test_load_configures() {
+    // MegaChip: Variante, Quirks und große Schrift
+    let mut processor = Processor::with_machine(MachineConfig::megachip()).unwrap();
+    processor.load(&[0x00, 0x11, 0xF0, 0x30, 0x12, 0x04]).unwrap();
+    assert_eq!(processor.rom_analysis().unwrap().instruction_set, InstructionSet::MegaChip);
+    assert_eq!(processor.variant, Variant::MegaChip);
+    assert_eq!(processor.quirks, Quirks::superchip());
+    assert!(processor.font().has_big());
+
+    // Die nächste ROM beginnt wieder mit den vorherigen Einstellungen
+    processor.load(&[0x03, 0x00, 0x12, 0x02]).unwrap();
+    assert!(processor.rom_analysis().unwrap().machine_code);
+    assert!(processor.machine().machine_code);
+    assert_eq!(processor.variant, Variant::Chip8);
+    assert_eq!(processor.quirks, Quirks::new());
+    assert!(!processor.font().has_big());
+
+    processor.load(&[0x02, 0xA0, 0x12, 0x02]).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8x);
+    assert!(!processor.machine().machine_code);
+
+    // SCHIP und XO-CHIP werden nicht unterstützt und daher nur gemeldet
+    processor.load(&[0xF0, 0x30, 0x00, 0xFD]).unwrap();
+    assert_eq!(processor.rom_analysis().unwrap().instruction_set, InstructionSet::Schip);
+    assert_eq!(processor.variant, Variant::Chip8);
+    assert_eq!(processor.quirks, Quirks::new());
+    assert!(!processor.font().has_big());
+
+    // Im Speicher von 4 KB läuft MegaChip nicht
+    let mut processor = Processor::new();
+    processor.load(&[0x00, 0x11, 0xF0, 0x30, 0x12, 0x04]).unwrap();
+    assert_eq!(processor.rom_analysis().unwrap().instruction_set, InstructionSet::MegaChip);
+    assert_eq!(processor.variant, Variant::Chip8);
+    assert_eq!(processor.quirks, Quirks::new());
+
+    // Ohne `auto_detect` wird nur analysiert
+    let mut processor = Processor::new();
+    processor.auto_detect = false;
+    processor.load(&[0x02, 0xA0, 0x12, 0x02]).unwrap();
+    assert_eq!(processor.rom_analysis().unwrap().instruction_set, InstructionSet::Chip8x);
+    assert_eq!(processor.variant, Variant::Chip8);
+}
+
+#[wasm_bindgen_test]
+fn test_manual_choice() {
+    // Von Hand gewählte Einstellungen ersetzt die Erkennung nicht, auch nicht mit CHIP-8
+    let mut processor = Processor::with_machine(MachineConfig::megachip()).unwrap();
+    processor.set_variant(Variant::Chip8).unwrap();
+    processor.set_quirks(Quirks::cosmac_vip());
+    processor.load(&[0x00, 0x11, 0xF0, 0x30, 0x12, 0x04]).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8);
+    assert_eq!(processor.quirks, Quirks::cosmac_vip());
+
+    // Nach dem Laden gewählte Einstellungen bleiben auch für die nächste ROM bestehen
+    let mut processor = Processor::with_machine(MachineConfig::megachip()).unwrap();
+    processor.load(&[0x00, 0x11, 0xF0, 0x30, 0x12, 0x04]).unwrap();
+    processor.set_quirks(Quirks::modern());
+    processor.load(&[0x02, 0xA0, 0x12, 0x02]).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8x);
+    assert_eq!(processor.quirks, Quirks::modern());
+}
@@ -0,0 +1,51 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::instruction::Instruction;
+use tomo::chip8::processor::Processor;
+
+#[wasm_bindgen_test]
+fn test_decode() {
+    assert_eq!(Instruction::decode(0x00E0), Instruction::Cls);
+    assert_eq!(Instruction::decode(0x8AB6), Instruction::Shr);
+    assert_eq!(Instruction::decode(0xD125), Instruction::Drw);
+    assert_eq!(Instruction::decode(0xF265), Instruction::LdVxI);
+    assert_eq!(Instruction::decode(0x5121), Instruction::AddNibbles);
+    assert_eq!(Instruction::decode(0x8AB8), Instruction::Invalid);
+}
+
+#[wasm_bindgen_test]
+fn test_self_modifying_code() {
+    let mut processor = Processor::new();
+    processor.load(&[
+        0x60, 0x12, // 0x200: LD V0, 0x12
+        0x61, 0x10, // 0x202: LD V1, 0x10
+        0xA2, 0x0C, // 0x204: LD I, 0x20C
+        0x22, 0x0C, // 0x206: CALL 0x20C
+        0xF1, 0x55, // 0x208: LD [I], V1 (0x20C wird zu JP 0x210)
+        0x12, 0x0C, // 0x20A: JP 0x20C
+        0x72, 0x01, // 0x20C: ADD V2, 1
+        0x00, 0xEE, // 0x20E: RET
+        0x73, 0x05, // 0x210: ADD V3, 5
+        0x12, 0x12, // 0x212: JP 0x212
+    ]).unwrap();
+    processor.run(12);
+    assert_eq!(processor.test_get_registers(2), 1);
+    assert_eq!(processor.test_get_registers(3), 5);
+    assert_eq!(processor.pc, 0x212);
+}
+
+#[wasm_bindgen_test]
+fn test_external_write_invalidates() {
+    let mut processor = Processor::new();
+    processor.load(&[
+        0x70, 0x01, // 0x200: ADD V0, 1
+        0x12, 0x00, // 0x202: JP 0x200
+    ]).unwrap();
+    processor.run(4);
+    assert_eq!(processor.test_get_registers(0), 2);
+
+    // ADD V0, 1 wird zu ADD V0, 3
+    processor.test_set_ram(0x201, 0x03);
+    processor.run(4);
+    assert_eq!(processor.test_get_registers(0), 8);
+}
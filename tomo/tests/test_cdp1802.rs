@@ -0,0 +1,109 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::cdp1802::Cdp1802;
+use tomo::chip8::machine::MachineConfig;
+use tomo::chip8::processor::{HaltReason, Processor};
+
+// Lädt ein CHIP-8 Programm ab 0x200 und Maschinensprache ab 0x300
+fn build_processor(program: &[u8], machine_code: &[u8]) -> Processor {
+    let mut rom = vec![0; 0x100 + machine_code.len()];
+    rom[..program.len()].copy_from_slice(program);
+    rom[0x100..].copy_from_slice(machine_code);
+    let mut processor = Processor::with_machine(MachineConfig::vip()).unwrap();
+    processor.load(&rom).unwrap();
+    processor
+}
+
+#[wasm_bindgen_test]
+fn test_hybrid_routine() {
+    let mut processor = build_processor(
+        &[
+            0xA4, 0x00, // 0x200: LD I, 0x400
+            0x03, 0x00, // 0x202: SYS 0x300
+            0x12, 0x04, // 0x204: JP 0x204
+        ],
+        &[
+            0xF8, 0x2A, // LDI 0x2A
+            0x5A, //       STR RA: M(I) = 0x2A
+            0x1A, //       INC RA
+            0x46, //       LDA R6: D = V3 (X aus 0x0300)
+            0xFC, 0x07, // ADI 7
+            0x26, //       DEC R6
+            0x56, //       STR R6: V3 = V3 + 7
+            0xD4, //       SEP R4
+        ],
+    );
+    processor.test_set_registers(3, 3);
+    processor.run(3);
+    assert_eq!(processor.halted(), Some(HaltReason::SelfJump));
+    assert_eq!(processor.test_get_ram(0x400), 0x2A);
+    assert_eq!(processor.i_reg, 0x401);
+    assert_eq!(processor.test_get_registers(3), 10);
+}
+
+#[wasm_bindgen_test]
+fn test_routine_writes_display() {
+    let mut processor = build_processor(
+        &[0x03, 0x00],
+        &[
+            0x9B, //       GHI RB: D = 0x0F
+            0xBF, //       PHI RF
+            0xF8, 0x08, // LDI 0x08
+            0xAF, //       PLO RF: RF = 0xF08 (Zeile 1)
+            0xF8, 0x81, // LDI 0x81
+            0x5F, //       STR RF
+            0xD4, //       SEP R4
+        ],
+    );
+    processor.run(1);
+    assert_eq!(processor.pc, 0x202);
+    assert!(processor.display.get_pixel(1, 0));
+    assert!(processor.display.get_pixel(1, 7));
+    assert!(!processor.display.get_pixel(1, 1));
+}
+
+#[wasm_bindgen_test]
+fn test_routine_limits() {
+    // Eine Endlosschleife (BR auf sich selbst) beendet das Programm
+    let mut processor = build_processor(&[0x03, 0x00], &[0x30, 0x00]);
+    assert_eq!(processor.run(1).halt, Some(HaltReason::MachineCode));
+
+    // Ohne CDP1802 ist 0NNN ungültig
+    let mut processor = Processor::new();
+    assert!(!processor.execute(0x0300).success);
+}
+
+#[wasm_bindgen_test]
+fn test_sys_0fd() {
+    // Mit Maschinensprache ist 00FD kein SCHIP-Exit, sondern ein Aufruf von 0x0FD
+    let mut processor = build_processor(&[0x00, 0xFD], &[]);
+    processor.test_set_ram(0x0FD, 0xD4); // SEP R4
+    let result = processor.run(1);
+    assert_eq!(result.halt, None);
+    assert_eq!(processor.pc, 0x202);
+
+    let mut processor = Processor::new();
+    assert_eq!(processor.execute(0x00FD).halt, Some(HaltReason::Exit));
+}
+
+#[wasm_bindgen_test]
+fn test_arithmetic() {
+    let mut ram = [
+        0xF8, 0x10, // LDI 0x10
+        0xFF, 0x20, // SMI 0x20: D = 0xF0, DF = 0 (Borgen)
+        0x3B, 0x07, // BNF 0x07
+        0xC4, //       NOP (übersprungen)
+        0x76, //       SHRC: D = 0x78, DF = 0
+        0xFC, 0x90, // ADI 0x90: D = 0x08, DF = 1
+        0x7C, 0x00, // ADCI 0: D = 0x09, DF = 0
+        0xD4, //       SEP R4
+    ];
+    let mut cpu = Cdp1802::new();
+    cpu.p = 3;
+    let result = cpu.call(&mut ram, 100);
+    assert!(result.returned);
+    assert_eq!(result.cycles, 7);
+    assert_eq!(result.written, None);
+    assert_eq!(cpu.d, 0x09);
+    assert!(!cpu.df);
+}
@@ -0,0 +1,78 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::color::{BACKGROUND_COLORS, FOREGROUND_COLORS};
+use tomo::chip8::machine::{MachineConfig, Variant};
+use tomo::chip8::processor::{Key, Processor};
+use tomo::chip8::render::Filter;
+
+fn build_processor() -> Processor {
+    let mut processor = Processor::with_machine(MachineConfig::chip8x()).unwrap();
+    processor.set_variant(Variant::Chip8x).unwrap();
+    processor
+}
+
+#[wasm_bindgen_test]
+fn test_colors() {
+    let mut processor = build_processor();
+    assert_eq!(processor.pc, 0x300);
+    processor.execute(0x02A0);
+    assert_eq!(processor.color_layer().background(), BACKGROUND_COLORS[1]);
+
+    // Zwei grobe Blöcke (16x4 Pixel) oben links werden grün
+    processor.test_set_registers(0, 0x10);
+    processor.test_set_registers(1, 0x00);
+    processor.test_set_registers(2, 4);
+    assert!(processor.execute(0xB020).success);
+    let layer = processor.color_layer();
+    assert_eq!(layer.foreground(3, 15), FOREGROUND_COLORS[4]);
+    assert_eq!(layer.foreground(4, 0), FOREGROUND_COLORS[1]);
+    assert_eq!(layer.foreground(0, 16), FOREGROUND_COLORS[1]);
+
+    // Zwei Zeilen eines Blocks ab (40, 10) werden weiß
+    processor.test_set_registers(0, 40);
+    processor.test_set_registers(1, 10);
+    processor.test_set_registers(2, 7);
+    processor.execute(0xB022);
+    let layer = processor.color_layer();
+    assert_eq!(layer.foreground(11, 47), FOREGROUND_COLORS[7]);
+    assert_eq!(layer.foreground(12, 47), FOREGROUND_COLORS[1]);
+
+    // Aktive Pixel in der Farbe ihres Blocks, inaktive im Hintergrund
+    processor.display.set_pixel(10, 40, true);
+    let image = processor.render(Filter::None);
+    let pixel = |x: usize, y: usize| image[(y * 64 + x) * 4..(y * 64 + x) * 4 + 3].to_vec();
+    assert_eq!(pixel(40, 10), [0xFF, 0xFF, 0xFF]);
+    assert_eq!(pixel(41, 10), [0x00, 0x00, 0x00]);
+}
+
+#[wasm_bindgen_test]
+fn test_instructions() {
+    let mut processor = build_processor();
+    processor.test_set_registers(0, 0x35);
+    processor.test_set_registers(1, 0x46);
+    processor.execute(0x5011);
+    assert_eq!(processor.test_get_registers(0), 0x73);
+
+    processor.test_set_registers(0, Key::K3 as u8);
+    processor.second_key_press(Key::K3);
+    let pc = processor.pc;
+    processor.execute(0xE0F2);
+    assert_eq!(processor.pc, pc + 4);
+    processor.execute(0xE0F5);
+    assert_eq!(processor.pc, pc + 6);
+
+    processor.test_set_registers(0, 0);
+    processor.execute(0xF0F8);
+    assert_eq!(processor.sound_frequency(), 27535.0);
+}
+
+#[wasm_bindgen_test]
+fn test_plain_chip8() {
+    // Ohne CHIP-8X bleibt BNNN ein Sprung und die Erweiterungen sind ungültig
+    let mut processor = Processor::new();
+    processor.execute(0xB300);
+    assert_eq!(processor.pc, 0x300);
+    assert!(!processor.execute(0x02A0).success);
+    assert!(!processor.execute(0x5011).success);
+    assert!(!processor.execute(0xE0F2).success);
+}
@@ -0,0 +1,93 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::controller::{Controller, MAX_SPEED, MIN_SPEED};
+use tomo::chip8::processor::{Processor, Register};
+
+// Zählt V0 hoch, DT startet bei 100
+const COUNTER: [u8; 8] = [
+    0x61, 0x64, // 0x200: LD V1, 100
+    0xF1, 0x15, // 0x202: LD DT, V1
+    0x70, 0x01, // 0x204: ADD V0, 1
+    0x12, 0x04, // 0x206: JP 0x204
+];
+
+fn build_processor() -> Processor {
+    let mut processor = Processor::new();
+    processor.load(&COUNTER).unwrap();
+    processor.tick_rate = 8;
+    processor
+}
+
+fn delay_timer(processor: &mut Processor) -> u8 {
+    processor.test_get_registers(Register::DT as usize)
+}
+
+#[wasm_bindgen_test]
+fn test_speed_limits() {
+    let mut controller = Controller::new();
+    controller.set_speed(100.0);
+    assert_eq!(controller.speed(), MAX_SPEED);
+    controller.set_speed(0.0);
+    assert_eq!(controller.speed(), MIN_SPEED);
+    controller.faster();
+    assert_eq!(controller.speed(), 0.5);
+    controller.turbo = true;
+    assert_eq!(controller.effective_speed(), MAX_SPEED);
+}
+
+#[wasm_bindgen_test]
+fn test_normal_and_fast_speed() {
+    let mut processor = build_processor();
+    let mut controller = Controller::new();
+    assert_eq!(controller.frame(&mut processor).unwrap().cycles, 8);
+    assert_eq!(delay_timer(&mut processor), 100);
+    controller.frame(&mut processor);
+    assert_eq!(delay_timer(&mut processor), 99);
+
+    controller.set_speed(4.0);
+    assert_eq!(controller.frame(&mut processor).unwrap().cycles, 32);
+    assert_eq!(delay_timer(&mut processor), 95);
+}
+
+#[wasm_bindgen_test]
+fn test_slow_motion() {
+    let mut processor = build_processor();
+    let mut controller = Controller::new();
+    controller.set_speed(0.25);
+    for _ in 0..4 {
+        assert_eq!(controller.frame(&mut processor).unwrap().cycles, 2);
+    }
+    // Die Timer laufen nur zu Beginn jedes emulierten Frames
+    assert_eq!(delay_timer(&mut processor), 100);
+    for _ in 0..8 {
+        controller.frame(&mut processor);
+    }
+    assert_eq!(delay_timer(&mut processor), 98);
+
+    // Bruchteile werden aufgespart
+    let mut processor = build_processor();
+    processor.tick_rate = 2;
+    assert!(controller.frame(&mut processor).is_none());
+    assert_eq!(controller.frame(&mut processor).unwrap().cycles, 1);
+}
+
+#[wasm_bindgen_test]
+fn test_pause_and_advance() {
+    let mut processor = build_processor();
+    let mut controller = Controller::new();
+    controller.pause();
+    assert!(controller.frame(&mut processor).is_none());
+    assert_eq!(processor.pc, 0x200);
+
+    let result = controller.advance(&mut processor);
+    assert_eq!(result.cycles, 8);
+    assert!(controller.paused());
+    assert!(controller.frame(&mut processor).is_none());
+
+    // Ein angefangener Frame wird zu Ende geführt
+    controller.resume();
+    controller.set_speed(0.5);
+    controller.frame(&mut processor);
+    assert_eq!(controller.advance(&mut processor).cycles, 4);
+    assert_eq!(controller.advance(&mut processor).cycles, 8);
+}
@@ -0,0 +1,150 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::machine::Variant;
+use tomo::chip8::processor::{HaltReason, Processor};
+use tomo::chip8::quirks::Quirks;
+use tomo::database::{sha1, DatabaseError, RomDatabase};
+
+const ROM: [u8; 2] = [0x12, 0x00];
+
+fn build_database() -> RomDatabase {
+    let programs = r##"[{
+        "title": "Test Program",
+        "authors": ["Tomo"],
+        "roms": {
+            "SHA": {
+                "file": "test.ch8",
+                "platforms": ["superchip"],
+                "quirkyPlatforms": { "superchip": { "shift": false } },
+                "tickrate": 30,
+                "colors": { "pixels": ["#102030", "#ffffff"], "buzzer": "#ff0000" },
+                "keys": { "up": 5, "a": 6 }
+            }
+        }
+    }]"##
+    .replace("SHA", &sha1(&ROM));
+    let hashes = format!(r#"{{ "{}": 0 }}"#, sha1(&ROM));
+    let platforms = r#"[{
+        "id": "superchip",
+        "name": "Superchip",
+        "defaultTickrate": 20,
+        "quirks": { "shift": true, "memoryLeaveIUnchanged": true, "jump": true }
+    }]"#;
+    RomDatabase::from_json(&programs, &hashes, platforms).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_sha1() {
+    assert_eq!(sha1(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+}
+
+#[wasm_bindgen_test]
+fn test_lookup() {
+    let database = build_database();
+    let info = database.identify(&ROM).unwrap();
+    assert_eq!(info.title, "Test Program");
+    assert_eq!(info.authors, vec!["Tomo".to_string()]);
+    assert_eq!(info.platform, "superchip");
+    assert_eq!(info.tick_rate, 30);
+    assert!(!info.quirks.shift);
+    assert!(info.quirks.jump);
+    assert!(info.quirks.memory_leave_i_unchanged);
+    assert_eq!(info.colors.unwrap().background, 0x102030);
+    assert_eq!(info.colors.unwrap().buzzer, 0xFF0000);
+    assert_eq!(info.keys.len(), 2);
+    assert!(database.identify(&[0x00]).is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_database() {
+    let error = RomDatabase::from_json("[]", r#"{ "abc": 3 }"#, "[]").err().unwrap();
+    assert_eq!(error, DatabaseError::MissingProgram { sha1: "abc".to_string(), index: 3 });
+    assert!(matches!(RomDatabase::from_json("{", "{}", "[]"), Err(DatabaseError::Json { .. })));
+}
+
+#[wasm_bindgen_test]
+fn test_embedded_database() {
+    let database = RomDatabase::embedded();
+    assert!(database.len() > 500);
+    let info = database.lookup("ea9af3c09b0d9e265fcd92bcc5d51a2939fdf27a").unwrap();
+    assert_eq!(info.title, "15 Puzzle");
+    assert_eq!(info.platform, "originalChip8");
+    assert_eq!(info.quirks, Quirks::cosmac_vip());
+    assert_eq!(info.tick_rate, 15);
+}
+
+#[wasm_bindgen_test]
+fn test_load_applies_settings() {
+    let mut processor = Processor::new();
+    processor.set_database(build_database());
+    processor.load(&ROM).unwrap();
+    assert_eq!(processor.rom_info().unwrap().title, "Test Program");
+    assert_eq!(processor.tick_rate, 30);
+    assert!(processor.quirks.jump);
+    assert_eq!(processor.colors.background, 0x102030);
+
+    processor.load(&[0x00, 0xE0]).unwrap();
+    assert!(processor.rom_info().is_none());
+    let default = Processor::new();
+    assert_eq!(processor.tick_rate, default.tick_rate);
+    assert_eq!(processor.quirks, default.quirks);
+    assert_eq!(processor.colors, default.colors);
+
+    let mut processor = Processor::new();
+    processor.set_database(build_database());
+    let mut quirks = Quirks::new();
+    quirks.logic = true;
+    processor.set_quirks(quirks);
+    processor.load(&ROM).unwrap();
+    assert_eq!(processor.quirks, quirks);
+    assert_eq!(processor.tick_rate, 30);
+
+    let mut processor = Processor::new();
+    processor.set_database(build_database());
+    processor.auto_configure = false;
+    processor.load(&ROM).unwrap();
+    assert!(processor.rom_info().is_some());
+    assert_eq!(processor.quirks, Quirks::new());
+}
+
+#[wasm_bindgen_test]
+fn test_load_applies_platform() {
+    let hybrid = [
+        0x02, 0x04, // 0x200: SYS 0x204
+        0x12, 0x02, // 0x202: JP 0x202
+        0xD4, //       0x204: SEP R4
+    ];
+    let chip8x = [0x02, 0xA0, 0x12, 0x02];
+    let programs = r##"[
+        { "title": "Hybrid", "roms": { "HYBRID": { "platforms": ["hybridVIP"] } } },
+        { "title": "Color", "roms": { "CHIP8X": { "platforms": ["chip8x"] } } }
+    ]"##
+    .replace("HYBRID", &sha1(&hybrid))
+    .replace("CHIP8X", &sha1(&chip8x));
+    let hashes = format!(r#"{{ "{}": 0, "{}": 1 }}"#, sha1(&hybrid), sha1(&chip8x));
+    let platforms = r#"[
+        { "id": "hybridVIP", "name": "Hybrid VIP", "defaultTickrate": 15, "quirks": {} },
+        { "id": "chip8x", "name": "CHIP-8X", "defaultTickrate": 15, "quirks": {} }
+    ]"#;
+    let database = RomDatabase::from_json(&programs, &hashes, platforms).unwrap();
+
+    let mut processor = Processor::new();
+    processor.set_database(database);
+    processor.load(&hybrid).unwrap();
+    assert!(processor.machine().machine_code);
+    processor.run(3);
+    assert_eq!(processor.halted(), Some(HaltReason::SelfJump));
+
+    processor.load(&chip8x).unwrap();
+    assert!(!processor.machine().machine_code);
+    assert_eq!(processor.variant, Variant::Chip8x);
+
+    // Die nächste ROM beginnt wieder mit den vorherigen Einstellungen
+    processor.load(&[0x00, 0xE0]).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8);
+
+    // Eine von Hand gewählte Variante bleibt erhalten
+    processor.set_variant(Variant::Chip8).unwrap();
+    processor.load(&chip8x).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8);
+}
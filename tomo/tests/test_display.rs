@@ -0,0 +1,69 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::{Display, DISPLAY_HEIGHT, DISPLAY_WIDTH, MAX_DISPLAY_HEIGHT};
+
+#[wasm_bindgen_test]
+fn test_pixels() {
+    let mut display = Display::new();
+    display.set_pixel(3, 0, true);
+    display.set_pixel(3, DISPLAY_WIDTH - 1, true);
+    assert!(display.get_pixel(3, 0));
+    assert!(display.get_pixel(3, DISPLAY_WIDTH - 1));
+    assert!(!display.get_pixel(3, 1));
+    assert_eq!(display.row(3), 0x8000_0000_0000_0001);
+
+    display.set_pixel(3, 0, false);
+    assert_eq!(display.row(3), 1);
+    assert!(display.take_changed());
+    assert!(!display.take_changed());
+
+    display.cls();
+    assert_eq!(display.row(3), 0);
+    assert!(display.take_changed());
+}
+
+#[wasm_bindgen_test]
+fn test_draw_row() {
+    let mut display = Display::new();
+    assert!(!display.draw_row(0, 4, 0b1010_0000, false));
+    assert!(display.get_pixel(0, 4));
+    assert!(!display.get_pixel(0, 5));
+    assert!(display.get_pixel(0, 6));
+
+    // Erneutes Zeichnen löscht die Pixel und meldet eine Kollision
+    assert!(display.draw_row(0, 4, 0b1010_0000, false));
+    assert_eq!(display.row(0), 0);
+    assert!(!display.draw_row(0, 4, 0b0101_0000, false));
+}
+
+#[wasm_bindgen_test]
+fn test_draw_row_edges() {
+    let mut display = Display::new();
+    display.draw_row(1, DISPLAY_WIDTH - 4, 0xFF, false);
+    assert_eq!(display.row(1), 0x0F);
+
+    display.draw_row(2, DISPLAY_WIDTH - 4, 0xFF, true);
+    assert_eq!(display.row(2), 0xF000_0000_0000_000F);
+}
+
+#[wasm_bindgen_test]
+fn test_display_is_small() {
+    assert!(core::mem::size_of::<Display>() <= MAX_DISPLAY_HEIGHT * 8 + 8);
+}
+
+#[wasm_bindgen_test]
+fn test_height() {
+    let mut display = Display::new();
+    assert_eq!(display.height(), DISPLAY_HEIGHT);
+    display.set_pixel(0, 0, true);
+    display.take_changed();
+
+    // Eine neue Höhe löscht das Display
+    display.set_height(MAX_DISPLAY_HEIGHT);
+    assert_eq!(display.height(), MAX_DISPLAY_HEIGHT);
+    assert_eq!(display.row(0), 0);
+    assert!(display.take_changed());
+    display.set_pixel(MAX_DISPLAY_HEIGHT - 1, 0, true);
+    assert!(display.get_pixel(MAX_DISPLAY_HEIGHT - 1, 0));
+    assert_eq!(Display::with_height(100).height(), MAX_DISPLAY_HEIGHT);
+}
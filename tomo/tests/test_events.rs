@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen_test::*;
+
+use tomo::chip8::events::Event;
+use tomo::chip8::processor::{HaltReason, Key, Processor, StopReason};
+
+fn build_processor(rom: &[u8]) -> (Processor, Rc<RefCell<Vec<Event>>>) {
+    let mut processor = Processor::new();
+    processor.load(rom).unwrap();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorded = events.clone();
+    processor.set_event_handler(Box::new(move |event| recorded.borrow_mut().push(event)));
+    (processor, events)
+}
+
+#[wasm_bindgen_test]
+fn test_key_wait_events() {
+    let (mut processor, events) = build_processor(&[
+        0xF3, 0x0A, // 0x200: LD V3, K
+        0x12, 0x02, // 0x202: JP 0x202
+    ]);
+    let frame = processor.run_frame();
+    assert_eq!(frame.stop, StopReason::WaitingForKey);
+    assert!(frame.key_wait_started && frame.waiting_for_key);
+    assert_eq!(*events.borrow(), vec![Event::KeyWaitStarted]);
+
+    // Erneutes Warten meldet nichts Neues
+    let frame = processor.run_frame();
+    assert!(!frame.key_wait_started);
+    assert_eq!(events.borrow().len(), 1);
+
+    processor.key_press(Key::K5);
+    let frame = processor.run_frame();
+    assert!(frame.key_wait_ended && !frame.waiting_for_key);
+    assert_eq!(frame.halt, Some(HaltReason::SelfJump));
+    assert_eq!(
+        *events.borrow(),
+        vec![Event::KeyWaitStarted, Event::KeyWaitEnded, Event::Halted(HaltReason::SelfJump)]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_sound_and_display_events() {
+    let (mut processor, events) = build_processor(&[
+        0x60, 0x02, // 0x200: LD V0, 2
+        0xF0, 0x18, // 0x202: LD ST, V0
+        0xD0, 0x05, // 0x204: DRW V0, V0, 5
+        0x12, 0x06, // 0x206: JP 0x206
+    ]);
+    let frame = processor.run_frame();
+    assert!(frame.display_changed && frame.sound_active);
+    assert!(frame.sound_started);
+    assert_eq!(
+        *events.borrow(),
+        vec![Event::SoundStarted, Event::Halted(HaltReason::SelfJump), Event::DisplayChanged]
+    );
+
+    events.borrow_mut().clear();
+    processor.run_frame();
+    let frame = processor.run_frame();
+    assert!(frame.sound_stopped && !frame.sound_active && !frame.display_changed);
+    assert_eq!(*events.borrow(), vec![Event::SoundStopped]);
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_opcode_event() {
+    let (mut processor, events) = build_processor(&[0xFF, 0xFF]);
+    let frame = processor.run_frame();
+    assert_eq!(frame.stop, StopReason::InvalidOpcode);
+    assert_eq!(frame.invalid_opcode, Some(0xFFFF));
+    assert_eq!(*events.borrow(), vec![Event::InvalidOpcode(0xFFFF)]);
+
+    // Ohne Empfänger werden die Ereignisse nur im Ergebnis gemeldet
+    processor.clear_event_handler();
+    processor.reset();
+    processor.load(&[0xFF, 0xFF]).unwrap();
+    assert_eq!(processor.run_frame().invalid_opcode, Some(0xFFFF));
+    assert_eq!(events.borrow().len(), 1);
+}
@@ -0,0 +1,72 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::font::{Font, FontError, FontSet};
+use tomo::chip8::processor::Processor;
+
+fn glyph(processor: &mut Processor, size: usize) -> Vec<u8> {
+    let start = processor.i_reg as usize;
+    (start..start + size).map(|address| processor.test_get_ram(address)).collect()
+}
+
+#[wasm_bindgen_test]
+fn test_font_sets() {
+    let mut processor = Processor::new();
+    processor.test_set_registers(0, 0x1);
+
+    processor.set_font(Font::from_set(FontSet::Chip48)).unwrap();
+    processor.execute(0xF029);
+    assert_eq!(processor.i_reg, 0x55);
+    assert_eq!(glyph(&mut processor, 5), [0x20, 0x60, 0x20, 0x20, 0x70]);
+
+    processor.set_font(Font::from_set(FontSet::Vip)).unwrap();
+    processor.execute(0xF029);
+    assert_eq!(processor.i_reg, 0x05);
+    assert_eq!(glyph(&mut processor, 5), [0x60, 0x20, 0x20, 0x20, 0x70]);
+
+    // Die Schrift bleibt nach dem Laden einer ROM erhalten
+    processor.load(&[0xF0, 0x29]).unwrap();
+    processor.test_set_registers(0, 0x1);
+    processor.tick();
+    assert_eq!(processor.font().set(), FontSet::Vip);
+    assert_eq!(glyph(&mut processor, 5), [0x60, 0x20, 0x20, 0x20, 0x70]);
+}
+
+#[wasm_bindgen_test]
+fn test_big_font() {
+    let mut processor = Processor::new();
+    processor.test_set_registers(3, 8);
+
+    // Ohne große Schrift ist FX30 ungültig
+    assert!(!processor.execute(0xF330).success);
+
+    processor.set_font(Font::from_set(FontSet::Schip)).unwrap();
+    assert!(processor.execute(0xF330).success);
+    assert_eq!(processor.i_reg, 0xA0 + 80);
+    assert_eq!(glyph(&mut processor, 10), [0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C]);
+}
+
+#[wasm_bindgen_test]
+fn test_custom_font() {
+    let data: Vec<u8> = (0..180).map(|index| index as u8).collect();
+    let mut font = Font::custom(&data).unwrap();
+    assert_eq!(font.set(), FontSet::Custom);
+    assert!(font.has_big());
+    font.address = 0x100;
+    font.big_address = 0x180;
+
+    let mut processor = Processor::new();
+    processor.set_font(font).unwrap();
+    processor.test_set_registers(0, 0xF);
+    processor.execute(0xF029);
+    assert_eq!(processor.i_reg, 0x100 + 75);
+    assert_eq!(glyph(&mut processor, 5), [75, 76, 77, 78, 79]);
+    processor.execute(0xF030);
+    assert_eq!(processor.i_reg, 0x180 + 150);
+
+    assert_eq!(Font::custom(&data[..100]), Err(FontError::Size(100)));
+    font.big_address = 0x140;
+    assert_eq!(processor.set_font(font), Err(FontError::Overlap));
+    font.address = 0xFC0;
+    assert_eq!(processor.set_font(font), Err(FontError::Address(0xFC0)));
+    assert_eq!(processor.font().address, 0x100);
+}
@@ -0,0 +1,83 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::processor::{HaltReason, Processor, StopReason};
+
+fn build_processor(rom: &[u8]) -> Processor {
+    let mut processor = Processor::new();
+    processor.load(rom).unwrap();
+    processor
+}
+
+#[wasm_bindgen_test]
+fn test_self_jump() {
+    let mut processor = build_processor(&[
+        0x60, 0x01, // 0x200: LD V0, 1
+        0x12, 0x02, // 0x202: JP 0x202
+    ]);
+    let result = processor.run(100);
+    assert_eq!(result.stop, StopReason::Halted);
+    assert_eq!(result.halt, Some(HaltReason::SelfJump));
+    assert_eq!(result.cycles, 2);
+    assert_eq!(processor.pc, 0x202);
+    assert_eq!(processor.halted(), Some(HaltReason::SelfJump));
+
+    // Weitere Aufrufe führen nichts mehr aus
+    assert_eq!(processor.run(100).cycles, 0);
+    assert_eq!(processor.tick().halt, Some(HaltReason::SelfJump));
+
+    processor.reset();
+    assert_eq!(processor.halted(), None);
+}
+
+#[wasm_bindgen_test]
+fn test_exit() {
+    let mut processor = build_processor(&[0x00, 0xFD]);
+    let output = processor.tick();
+    assert!(output.success);
+    assert_eq!(output.halt, Some(HaltReason::Exit));
+    assert_eq!(processor.run(10).stop, StopReason::Halted);
+}
+
+#[wasm_bindgen_test]
+fn test_end_of_memory() {
+    let mut processor = build_processor(&[
+        0x1F, 0xFE, // 0x200: JP 0xFFE
+    ]);
+    processor.test_set_ram(0xFFE, 0x60);
+    processor.test_set_ram(0xFFF, 0x07);
+    let result = processor.run(10);
+    assert_eq!(result.stop, StopReason::Halted);
+    assert_eq!(result.halt, Some(HaltReason::EndOfMemory));
+    assert_eq!(result.cycles, 2);
+    assert_eq!(processor.test_get_registers(0), 7);
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_address() {
+    // FX1E schiebt I über das Ende des RAMs, FX55 würde dahinter schreiben
+    let mut processor = build_processor(&[
+        0xAF, 0xFE, // 0x200: LD I, 0xFFE
+        0x60, 0x02, // 0x202: LD V0, 2
+        0xF0, 0x1E, // 0x204: ADD I, V0
+        0xF1, 0x55, // 0x206: LD [I], V1
+    ]);
+    let result = processor.run(10);
+    assert_eq!(result.stop, StopReason::Halted);
+    assert_eq!(result.halt, Some(HaltReason::InvalidAddress));
+    assert_eq!(processor.pc, 0x206);
+
+    // Sprites, FX33 und FX65 ebenso
+    for opcode in [0xD015, 0xF033, 0xF165] {
+        let mut processor = build_processor(&[]);
+        processor.i_reg = 0xFFF;
+        processor.execute(opcode);
+        assert_eq!(processor.halted(), Some(HaltReason::InvalidAddress), "{:#X}", opcode);
+    }
+    // Abgeschnittene Zeilen eines Sprites werden nicht gelesen
+    let mut processor = build_processor(&[]);
+    processor.quirks.wrap = false;
+    processor.i_reg = 0xFFF;
+    processor.test_set_registers(1, 31);
+    processor.execute(0xD015);
+    assert_eq!(processor.halted(), None);
+}
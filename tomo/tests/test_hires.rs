@@ -0,0 +1,59 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::machine::{MachineConfig, Variant};
+use tomo::chip8::processor::Processor;
+use tomo::chip8::render::Filter;
+use tomo::chip8::{Colors, MAX_DISPLAY_HEIGHT};
+
+// Hires-ROM: 1260 am Anfang, das Programm liegt bei 0x2C0
+fn hires_rom(program: &[u8]) -> Vec<u8> {
+    let mut rom = vec![0; 0xC0 + program.len()];
+    rom[..2].copy_from_slice(&[0x12, 0x60]);
+    rom[0xC0..].copy_from_slice(program);
+    rom
+}
+
+#[wasm_bindgen_test]
+fn test_detection() {
+    let mut processor = Processor::new();
+    processor.load(&hires_rom(&[0x12, 0xC0])).unwrap();
+    assert_eq!(processor.variant, Variant::Hires);
+    assert_eq!(processor.pc, 0x2C0);
+    assert_eq!(processor.display.height(), MAX_DISPLAY_HEIGHT);
+    assert_eq!(processor.render(Filter::None).len(), 64 * 64 * 4);
+    assert_eq!(processor.display.to_png(&Colors::default(), 1)[20..24], 64u32.to_be_bytes());
+
+    // Ohne Signatur bleibt es beim normalen CHIP-8
+    let mut processor = Processor::new();
+    processor.load(&[0x12, 0x00]).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8);
+    assert_eq!(processor.display.height(), 32);
+    assert!(!processor.execute(0x0230).success);
+
+    // Eine andere Ladeadresse schließt die Signatur aus
+    let mut processor = Processor::with_machine(MachineConfig::eti660()).unwrap();
+    processor.load(&hires_rom(&[])).unwrap();
+    assert_eq!(processor.variant, Variant::Chip8);
+}
+
+#[wasm_bindgen_test]
+fn test_drawing() {
+    let mut processor = Processor::new();
+    processor.load(&hires_rom(&[
+        0x60, 0x28, // 0x2C0: LD V0, 40
+        0xA0, 0x00, // 0x2C2: LD I, 0x000 (Glyphe "0")
+        0xD0, 0x05, // 0x2C4: DRW V0, V0, 5
+        0x02, 0x30, // 0x2C6: CLS (Hires)
+    ])).unwrap();
+    processor.run(3);
+    assert!(processor.display.get_pixel(44, 40));
+    assert!(processor.run(1).display_changed);
+    assert!(!processor.display.get_pixel(44, 40));
+
+    // Sprites laufen erst nach Zeile 63 oben weiter
+    processor.test_set_registers(0, 62);
+    processor.execute(0xD005);
+    assert!(processor.display.get_pixel(63, 62));
+    assert!(processor.display.get_pixel(0, 62));
+    assert!(!processor.display.get_pixel(32, 62));
+}
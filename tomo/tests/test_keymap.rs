@@ -0,0 +1,97 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::processor::Key;
+use tomo::database::RomDatabase;
+use tomo::keymap::{key_from_hex, Keymap, Layout};
+
+fn hex(value: u8) -> Key {
+    key_from_hex(value).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_layouts() {
+    // Der Processor verwendet den Wert der Taste, nicht ihren Namen
+    assert_eq!(hex(0x0), Key::K1);
+    assert_eq!(hex(0x9), Key::K0);
+    assert_eq!(hex(0xC) as u8, 0xC);
+    assert_eq!(key_from_hex(0x10), None);
+
+    let keymap = Keymap::new(Layout::Standard);
+    assert_eq!(keymap.map_code("Digit1"), Some(hex(0x1)));
+    assert_eq!(keymap.map_code("KeyR"), Some(hex(0xD)));
+    assert_eq!(keymap.map_code("KeyX"), Some(hex(0x0)));
+    assert_eq!(keymap.map_code("KeyV"), Some(hex(0xF)));
+    assert_eq!(keymap.map_code("KeyP"), None);
+    // Scancodes (Set 1) landen auf denselben Tasten
+    assert_eq!(keymap.map_scancode(0x10), Some(hex(0x4)));
+    assert_eq!(keymap.label(hex(0x4)).unwrap(), "Q");
+
+    let keymap = Keymap::new(Layout::Azerty);
+    assert_eq!(keymap.map_code("KeyQ"), Some(hex(0x4)));
+    assert_eq!(keymap.label(hex(0x4)).unwrap(), "A");
+    assert_eq!(keymap.label(hex(0xA)).unwrap(), "W");
+    assert_eq!(Keymap::new(Layout::Qwertz).label(hex(0xA)).unwrap(), "Y");
+
+    let keymap = Keymap::new(Layout::Numpad);
+    assert_eq!(keymap.map_code("Numpad8"), Some(hex(0x8)));
+    assert_eq!(keymap.map_scancode(0xE01C), Some(hex(0xE)));
+    assert_eq!(keymap.map_code("Digit1"), None);
+    assert_eq!(keymap.label(hex(0xB)).unwrap(), "*");
+}
+
+#[wasm_bindgen_test]
+fn test_remapping() {
+    let mut keymap = Keymap::default();
+    keymap.bind("ArrowUp", hex(0x5));
+    assert_eq!(keymap.codes(hex(0x5)), vec!["ArrowUp".to_string(), "KeyW".to_string()]);
+    keymap.rebind(hex(0x5), "Space");
+    assert_eq!(keymap.codes(hex(0x5)), vec!["Space".to_string()]);
+    assert_eq!(keymap.map_code("KeyW"), None);
+    keymap.unbind("Space");
+    assert_eq!(keymap.map_code("Space"), None);
+    keymap.set_layout(Layout::Standard);
+    assert_eq!(keymap.map_code("KeyW"), Some(hex(0x5)));
+}
+
+#[wasm_bindgen_test]
+fn test_rom_bindings() {
+    let mut keymap = Keymap::default();
+    keymap.bind_for_rom("pong", "KeyW", hex(0x1));
+    assert_eq!(keymap.map_code("KeyW"), Some(hex(0x5)));
+    keymap.select_rom(Some("pong".to_string()));
+    assert_eq!(keymap.map_code("KeyW"), Some(hex(0x1)));
+    assert_eq!(keymap.map_code("KeyS"), Some(hex(0x8)));
+    keymap.select_rom(Some("other".to_string()));
+    assert_eq!(keymap.map_code("KeyW"), Some(hex(0x5)));
+    keymap.clear_rom("pong");
+    keymap.select_rom(Some("pong".to_string()));
+    assert_eq!(keymap.map_code("KeyW"), Some(hex(0x5)));
+}
+
+#[wasm_bindgen_test]
+fn test_database_keys() {
+    // "Hidden": up 2, left 4, right 6, down 8, a 5
+    let info = RomDatabase::embedded().lookup("050f07a54371da79f924dd0227b89d07b4f2aed0").unwrap();
+    let mut keymap = Keymap::default();
+    keymap.bind_rom_keys(&info);
+    assert_eq!(keymap.map_code("ArrowUp"), None);
+    keymap.select_rom(Some(info.sha1.clone()));
+    assert_eq!(keymap.map_code("ArrowUp"), Some(hex(0x2)));
+    assert_eq!(keymap.map_code("ArrowDown"), Some(hex(0x8)));
+    assert_eq!(keymap.map_code("Space"), Some(hex(0x5)));
+    assert_eq!(keymap.map_code("KeyQ"), Some(hex(0x4)));
+}
+
+#[wasm_bindgen_test]
+fn test_serialization() {
+    let mut keymap = Keymap::new(Layout::Qwertz);
+    keymap.rebind(hex(0xA), "KeyY");
+    keymap.bind_for_rom("pong", "ArrowUp", hex(0x1));
+    let json = keymap.to_json();
+    assert!(json.contains(r#""KeyY":"A""#));
+    assert!(json.contains(r#""layout":"qwertz""#));
+    assert_eq!(Keymap::from_json(&json).unwrap(), keymap);
+
+    assert!(Keymap::from_json(r#"{ "layout": "standard", "bindings": { "KeyQ": "G" } }"#).is_err());
+    assert!(Keymap::from_json(r#"{ "layout": "dvorak", "bindings": {} }"#).is_err());
+}
@@ -0,0 +1,101 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::machine::{LoadError, MachineConfig, MachineError, MAX_STACK_DEPTH};
+use tomo::chip8::processor::{HaltReason, Processor};
+
+#[wasm_bindgen_test]
+fn test_load_address() {
+    let mut processor = Processor::with_machine(MachineConfig::eti660()).unwrap();
+    assert_eq!(processor.pc, 0x600);
+    processor.load(&[0x60, 0x2A]).unwrap();
+    assert_eq!(processor.test_get_ram(0x600), 0x60);
+    processor.tick();
+    assert_eq!(processor.test_get_registers(0), 0x2A);
+
+    // Die ROM liegt bei 0x200, das Programm beginnt aber erst bei 0x2C0
+    let mut processor = Processor::with_machine(MachineConfig::hires()).unwrap();
+    processor.load(&[0x12, 0x60]).unwrap();
+    assert_eq!(processor.test_get_ram(0x200), 0x12);
+    assert_eq!(processor.pc, 0x2C0);
+}
+
+#[wasm_bindgen_test]
+fn test_rom_too_large() {
+    let machine = MachineConfig { ram_size: 0x800, ..MachineConfig::new() };
+    let mut processor = Processor::with_machine(machine).unwrap();
+    assert_eq!(processor.load(&[0; 0x600]), Ok(0x600));
+    assert_eq!(processor.load(&[0; 0x601]), Err(LoadError { size: 0x601, available: 0x600 }));
+    // Die zuvor geladene ROM bleibt erhalten
+    assert_eq!(processor.pc, 0x200);
+
+    let mut processor = Processor::new();
+    assert!(processor.load(&[0; 0xE00]).is_ok());
+    assert!(processor.load(&[0; 0xE01]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_config() {
+    let config = MachineConfig::new();
+    assert_eq!(Processor::with_machine(MachineConfig { ram_size: 0x100, ..config }).err(), Some(MachineError::RamSize(0x100)));
+    assert_eq!(MachineConfig { load_address: 0x1000, ..config }.validate(), Err(MachineError::LoadAddress(0x1000)));
+    assert_eq!(MachineConfig { initial_pc: 0xFFF, ..config }.validate(), Err(MachineError::InitialPc(0xFFF)));
+    assert_eq!(MachineConfig { stack_depth: 0, ..config }.validate(), Err(MachineError::StackDepth(0)));
+    assert!(MachineConfig { ram_size: 0x10000, stack_depth: MAX_STACK_DEPTH, ..config }.validate().is_ok());
+}
+
+#[wasm_bindgen_test]
+fn test_stack_depth() {
+    // Ruft sich selbst rekursiv auf
+    let rom = [0x22, 0x00];
+    let mut processor = Processor::new();
+    processor.load(&rom).unwrap();
+    assert_eq!(processor.run(100).halt, Some(HaltReason::StackOverflow));
+    assert_eq!(processor.sp, 16);
+
+    let machine = MachineConfig { stack_depth: 32, ..MachineConfig::new() };
+    let mut processor = Processor::with_machine(machine).unwrap();
+    processor.load(&rom).unwrap();
+    assert_eq!(processor.run(100).halt, Some(HaltReason::StackOverflow));
+    assert_eq!(processor.sp, 32);
+
+    processor.load(&[0x00, 0xEE]).unwrap();
+    assert_eq!(processor.run(1).halt, Some(HaltReason::StackUnderflow));
+}
+
+#[wasm_bindgen_test]
+fn test_vip_memory() {
+    let mut processor = Processor::with_machine(MachineConfig::vip()).unwrap();
+    processor.load(&[
+        0x60, 0x05, // 0x200: LD V0, 5
+        0xAE, 0xF3, // 0x202: LD I, 0xEF3
+        0xF0, 0x55, // 0x204: LD [I], V0 (schreibt V3)
+        0x22, 0x0A, // 0x206: CALL 0x20A
+        0x00, 0x00, // 0x208
+        0xA0, 0x00, // 0x20A: LD I, 0x000 (Glyphe "0")
+        0xD0, 0x05, // 0x20C: DRW V0, V0, 5
+        0x00, 0xEE, // 0x20E: RET
+    ]).unwrap();
+    processor.run(3);
+    assert_eq!(processor.test_get_ram(0xEF0), 5);
+    assert_eq!(processor.test_get_registers(3), 5);
+
+    processor.run(1);
+    assert_eq!(processor.test_get_ram(0xECE), 0x02);
+    assert_eq!(processor.test_get_ram(0xECF), 0x08);
+
+    processor.run(2);
+    // Glyphe bei (5, 5): Zeile 5, Bytes 0 und 1
+    assert_eq!(processor.test_get_ram(0xF00 + 5 * 8), 0x07);
+    assert_eq!(processor.test_get_ram(0xF00 + 5 * 8 + 1), 0x80);
+
+    // Schreibzugriffe auf die Bereiche wirken auf Display und Stack
+    processor.test_set_ram(0xF00, 0xFF);
+    assert!(processor.display.get_pixel(0, 7));
+    processor.test_set_ram(0xECF, 0x20);
+    processor.run(1);
+    assert_eq!(processor.pc, 0x220);
+
+    // Der Stack begrenzt die Größe der ROM
+    assert!(processor.load(&[0; 0xEA0 - 0x200]).is_ok());
+    assert!(processor.load(&[0; 0xEA1 - 0x200]).is_err());
+}
@@ -0,0 +1,141 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::machine::{MachineConfig, MachineError, Variant};
+use tomo::chip8::megachip::{BlendMode, DigitizedSound, MegaChip, MEGACHIP_WIDTH};
+use tomo::chip8::processor::{HaltReason, Processor};
+use tomo::chip8::render::Filter;
+
+// Programm ab 0x200, Palette und Sprite ab 0x10000
+fn build_processor(program: &[u8]) -> Processor {
+    let mut rom = vec![0; 0x10000 - 0x200];
+    rom[..program.len()].copy_from_slice(program);
+    rom.extend_from_slice(&[
+        0xFF, 0xFF, 0x00, 0x00, // Farbe 1: Rot
+        0xFF, 0x00, 0x00, 0xFF, // Farbe 2: Blau
+        0x01, 0x02, //             Sprite (2x1)
+    ]);
+    let mut processor = Processor::with_machine(MachineConfig::megachip()).unwrap();
+    processor.set_variant(Variant::MegaChip).unwrap();
+    processor.load(&rom).unwrap();
+    processor
+}
+
+fn pixel(image: &[u8], x: usize, y: usize) -> u32 {
+    let index = (y * MEGACHIP_WIDTH + x) * 4;
+    u32::from_be_bytes([0, image[index], image[index + 1], image[index + 2]])
+}
+
+#[wasm_bindgen_test]
+fn test_sprites() {
+    let mut processor = build_processor(&[
+        0x00, 0x11, //             0x200: MegaChip-Modus an
+        0x01, 0x01, 0x00, 0x00, // 0x202: LDHI 0x10000
+        0x02, 0x02, //             0x206: Palette mit 2 Farben
+        0x01, 0x01, 0x00, 0x08, // 0x208: LDHI 0x10008
+        0x03, 0x02, //             0x20C: Breite 2
+        0x04, 0x01, //             0x20E: Höhe 1
+        0x09, 0x01, //             0x210: Kollision mit Farbe 1
+        0x60, 0x0A, //             0x212: LD V0, 10
+        0xD0, 0x01, //             0x214: DRW V0, V0
+        0xD0, 0x01, //             0x216: DRW V0, V0
+        0x00, 0xE0, //             0x218: Frame anzeigen
+    ]);
+    processor.run(9);
+    assert!(processor.megachip_enabled());
+    assert_eq!(processor.i_reg, 0x10008);
+    assert_eq!(processor.test_get_registers(0xF), 0);
+    // Noch nicht angezeigt
+    assert_eq!(pixel(&processor.render(Filter::None), 10, 10), 0);
+
+    processor.run(1);
+    assert_eq!(processor.test_get_registers(0xF), 1);
+    assert!(processor.run(1).display_changed);
+    let image = processor.render(Filter::None);
+    assert_eq!((processor.render_width(), processor.render_height()), (256, 192));
+    assert_eq!(image.len(), 256 * 192 * 4);
+    assert_eq!(pixel(&image, 10, 10), 0xFF0000);
+    assert_eq!(pixel(&image, 11, 10), 0x0000FF);
+    assert_eq!(pixel(&image, 12, 10), 0);
+
+    // 0010 schaltet zurück auf das normale Display
+    processor.execute(0x0010);
+    assert!(!processor.megachip_enabled());
+    assert_eq!(processor.render(Filter::None).len(), 64 * 32 * 4);
+}
+
+#[wasm_bindgen_test]
+fn test_buffer() {
+    let mut megachip = MegaChip::new();
+    megachip.load_palette(&[0xFF, 0x80, 0x40, 0x20, 0xFF, 0x80, 0x80, 0x80]);
+    megachip.sprite_width = 1;
+    megachip.sprite_height = 1;
+    megachip.draw_sprite(0, 0, &[1]);
+    megachip.blend = BlendMode::Add;
+    megachip.draw_sprite(0, 0, &[2]);
+    // Außerhalb des Bildspeichers wird abgeschnitten
+    megachip.draw_sprite(256, 0, &[1]);
+
+    megachip.scroll_horizontal(4);
+    megachip.scroll_vertical(2);
+    megachip.alpha = 0x80;
+    megachip.present();
+    let frame = megachip.frame();
+    assert_eq!(frame[0], 0);
+    assert_eq!(frame[2 * MEGACHIP_WIDTH + 4], 0x806050);
+}
+
+#[wasm_bindgen_test]
+fn test_sound() {
+    let sound = DigitizedSound::parse(&[0x1F, 0x40, 0x00, 0x00, 0x03, 0x00, 0x80, 0xFF, 0x00, 0x12], true).unwrap();
+    assert_eq!(sound.sample_rate, 8000);
+    assert_eq!(sound.samples(), [0x80, 0xFF, 0x00]);
+    assert!(DigitizedSound::parse(&[0x1F, 0x40], false).is_none());
+
+    let mut processor = build_processor(&[0x00, 0x11, 0xA2, 0x08, 0x06, 0x01, 0x00, 0x00, 0x1F, 0x40, 0x00, 0x00, 0x01, 0x00, 0x7F]);
+    processor.run(3);
+    let sound = processor.digitized_sound().unwrap();
+    assert!(!sound.looping);
+    assert_eq!(sound.samples(), [0x7F]);
+    processor.execute(0x0700);
+    assert!(processor.digitized_sound().is_none());
+
+    // Ohne MegaChip sind die Befehle ungültig
+    let mut processor = Processor::new();
+    assert!(!processor.execute(0x0011).success);
+    assert!(!processor.megachip_enabled());
+}
+
+#[wasm_bindgen_test]
+fn test_small_ram() {
+    // MegaChip braucht den großen RAM
+    let mut processor = Processor::new();
+    assert_eq!(processor.set_variant(Variant::MegaChip), Err(MachineError::Variant(Variant::MegaChip)));
+    assert_eq!(processor.variant, Variant::Chip8);
+
+    // Auch von Hand gesetzt lässt sich der MegaChip-Modus dann nicht einschalten
+    processor.variant = Variant::MegaChip;
+    assert!(!processor.execute(0x0011).success);
+    assert!(!processor.megachip_enabled());
+
+    let mut processor = Processor::new();
+    processor.load(&[0x00, 0x11, 0x01, 0x01, 0x00, 0x00, 0xF0, 0x55]).unwrap();
+    processor.run(10);
+    assert!(!processor.megachip_enabled());
+    assert_eq!(processor.i_reg, 0);
+}
+
+#[wasm_bindgen_test]
+fn test_ldhi_bounds() {
+    // LDHI kann I hinter das Ende des RAMs setzen, FX55 hält dann an
+    let mut processor = build_processor(&[
+        0x00, 0x11, //             0x200: MegaChip an
+        0x01, 0x01, 0x00, 0x00, // 0x202: LDHI 0x10000
+        0xF0, 0x55, //             0x206: LD [I], V0
+        0x01, 0xFF, 0xFF, 0xFF, // 0x208: LDHI 0xFFFFFF
+        0xF1, 0x55, //             0x20C: LD [I], V1
+    ]);
+    let result = processor.run(10);
+    assert_eq!(result.halt, Some(HaltReason::InvalidAddress));
+    assert_eq!(processor.pc, 0x20C);
+    assert_eq!(processor.i_reg, 0xFFFFFF);
+}
@@ -0,0 +1,259 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::machine::LoadError;
+use tomo::chip8::processor::Processor;
+use tomo::chip8::quirks::Quirks;
+use tomo::octo::{compile, Cartridge, CartridgeError, CompileError, OctoOptions};
+
+// Schreibt Codes mit fester Länge, niederwertigste Bits zuerst
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u32, size: u32) {
+        self.buffer |= code << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+}
+
+// GIF mit 4 Farben, dessen LZW-Daten nach je 2 Pixeln zurückgesetzt werden,
+// damit die Code-Länge fest bei 3 Bit bleibt
+fn build_gif(frames: &[Vec<u8>], width: u16) -> Vec<u8> {
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&width.to_le_bytes());
+    gif.extend_from_slice(&1u16.to_le_bytes());
+    gif.extend_from_slice(&[0x81, 0, 0]);
+    gif.extend_from_slice(&[0x00, 0x00, 0x00, 0x55, 0x55, 0x55, 0xAA, 0xAA, 0xAA, 0xFF, 0xFF, 0xFF]);
+    for frame in frames {
+        gif.push(0x2C);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&width.to_le_bytes());
+        gif.extend_from_slice(&(frame.len() as u16).div_ceil(width).to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x02]);
+        let mut writer = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+        for pair in frame.chunks(2) {
+            writer.write(4, 3);
+            for index in pair {
+                writer.write(u32::from(*index), 3);
+            }
+        }
+        writer.write(5, 3);
+        writer.write(0, 7);
+        for block in writer.bytes.chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0);
+    }
+    gif.push(0x3B);
+    gif
+}
+
+// Verteilt die Cartridge-Daten auf Frames mit je `frame_size` Pixeln
+fn build_cartridge(json: &str, frame_size: usize) -> Vec<u8> {
+    let mut payload = (json.len() as u32).to_be_bytes().to_vec();
+    payload.extend_from_slice(json.as_bytes());
+    let pixels: Vec<u8> = payload
+        .iter()
+        .flat_map(|byte| [byte >> 6, byte >> 4, byte >> 2, *byte].map(|pixel| pixel & 0b11))
+        .collect();
+    let frames: Vec<Vec<u8>> = pixels.chunks(frame_size).map(|frame| frame.to_vec()).collect();
+    build_gif(&frames, 16)
+}
+
+const JSON: &str = r##"{
+    "program": ": main\n  clear # CLS\n: spin\n  jump spin -1 0b101\n",
+    "options": {
+        "tickrate": 500,
+        "fillColor": "#FFCC00",
+        "backgroundColor": "#996600",
+        "shiftQuirks": true,
+        "clipQuirks": true,
+        "jumpQuirks": false,
+        "screenRotation": 90
+    }
+}"##;
+
+#[wasm_bindgen_test]
+fn test_decode_cartridge() {
+    let cartridge = Cartridge::decode(&build_cartridge(JSON, 128)).unwrap();
+    assert!(cartridge.program.starts_with(": main"));
+    assert_eq!(cartridge.options.tickrate, 500);
+    assert_eq!(cartridge.options.screen_rotation, 90);
+    assert_eq!(cartridge.rom().unwrap(), vec![0x00, 0xE0, 0x12, 0x02, 0xFF, 0x05]);
+}
+
+#[wasm_bindgen_test]
+fn test_load_cartridge() {
+    let mut processor = Processor::new();
+    let size = processor.load_cartridge(&build_cartridge(JSON, 64)).unwrap();
+    assert_eq!(size, 6);
+    assert_eq!(processor.test_get_ram(0x200), 0x00);
+    assert_eq!(processor.test_get_ram(0x202), 0x12);
+    assert_eq!(processor.tick_rate, 500);
+    assert_eq!(processor.colors.foreground, 0xFFCC00);
+    assert_eq!(processor.colors.background, 0x996600);
+    assert_eq!(processor.screen_rotation, 90);
+    assert!(processor.quirks.shift);
+    assert!(!processor.quirks.wrap);
+    assert!(!processor.quirks.jump);
+    assert!(!processor.quirks.memory_leave_i_unchanged);
+}
+
+#[wasm_bindgen_test]
+fn test_compile() {
+    let source = "
+: main
+  clear
+  v0 := 5
+  i := shape
+  loop
+    sprite v0 v1 5
+    v0 += 1
+    while v0 != 10
+  again
+  if v2 == v3 then v1 := random 0xFF
+  if v1 > 3 begin
+    draw
+  else
+    :call draw
+  end
+  :unpack 0xA shape
+  jump main
+
+: draw
+  return
+
+: shape
+  0xF0 0x90 0xF0 0x90 0x90
+";
+    assert_eq!(
+        compile(source).unwrap(),
+        vec![
+            0x00, 0xE0, 0x60, 0x05, 0xA2, 0x2A, // main steht am Anfang, kein Sprung
+            0xD0, 0x15, 0x70, 0x01, 0x40, 0x0A, 0x12, 0x10, 0x12, 0x06, // loop / while / again
+            0x92, 0x30, 0xC1, 0xFF, // if then
+            0x6F, 0x03, 0x8F, 0x15, 0x4F, 0x01, 0x12, 0x20, 0x22, 0x28, 0x12, 0x22, 0x22, 0x28, // if begin else end
+            0x60, 0xA2, 0x61, 0x2A, 0x12, 0x00, 0x00, 0xEE, // unpack, jump, draw
+            0xF0, 0x90, 0xF0, 0x90, 0x90,
+        ]
+    );
+
+    // Makros, Konstanten und Ausdrücke (von rechts nach links), main steht nicht am Anfang
+    let source = r#"
+:const SPEED 3
+:alias x v4
+:macro add-speed reg { reg += SPEED }
+:calc DOUBLE { SPEED * 2 + 1 }
+: data
+  :byte { DOUBLE }
+  :pointer main
+: main
+  add-speed x
+  :next self x := 0
+  i := long data
+  x := OCTO_KEY_W
+:stringmode text "AB" { :byte { VALUE + CHAR } }
+  text "BA"
+:assert "size" { HERE == 0x211 }
+"#;
+    assert_eq!(
+        compile(source).unwrap(),
+        vec![0x12, 0x05, 0x09, 0x02, 0x05, 0x74, 0x03, 0x64, 0x00, 0xF0, 0x00, 0x02, 0x02, 0x64, 0x05, 0x43, 0x41]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_compile_errors() {
+    let error = |source: &str| compile(source).unwrap_err();
+    assert_eq!(error(": main\n  v0 := vz\n").line, 2);
+    assert_eq!(error(": main\n  jump nowhere\n").message, "Undefined name 'nowhere'");
+    assert_eq!(error(": main\n  loop\n  v0 += 1\n").line, 2);
+    assert_eq!(error(": main\n  v0 := 256\n").message, "The value 256 does not fit into a byte");
+    assert!(error("0x00 0xE0").message.contains("main"));
+
+    let json = r#"{ "program": ": main\n  v0 := vz\n", "options": { "tickrate": 7 } }"#;
+    let cartridge = Cartridge::decode(&build_cartridge(json, 1024)).unwrap();
+    assert_eq!(cartridge.options.tickrate, 7);
+    assert_eq!(
+        cartridge.rom(),
+        Err(CartridgeError::Source(CompileError { line: 2, message: "Expected a number, got 'vz'".to_string() }))
+    );
+
+    let mut processor = Processor::new();
+    assert!(processor.load_cartridge(&build_cartridge(json, 1024)).is_err());
+    assert_eq!(processor.tick_rate, tomo::chip8::DEFAULT_TICK_RATE);
+
+    // Der Fehler von `load` wird unverändert weitergegeben
+    let json = format!(r#"{{ "program": ": main\n{}" }}"#, "0x00 ".repeat(4000));
+    assert_eq!(
+        processor.load_cartridge(&build_cartridge(&json, 1024)),
+        Err(CartridgeError::Load(LoadError { size: 4000, available: 3584 }))
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_cartridge() {
+    assert!(matches!(Cartridge::decode(b"PNG"), Err(CartridgeError::Gif(_))));
+    assert!(matches!(Cartridge::decode(&build_gif(&[vec![0; 8]], 8)), Err(CartridgeError::Payload(_))));
+    assert!(matches!(Cartridge::decode(&build_cartridge("{", 64)), Err(CartridgeError::Json(_))));
+
+    // Riesige Maße im Header werden nicht reserviert, sondern abgelehnt
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&[1, 0, 1, 0, 0, 0, 0]);
+    gif.extend_from_slice(&[0x2C, 0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x02, 0x00, 0x3B]);
+    assert_eq!(Cartridge::decode(&gif), Err(CartridgeError::Gif("frame too large")));
+}
+
+#[wasm_bindgen_test]
+fn test_options_defaults() {
+    let options = OctoOptions::from_json(r#"{ "shiftQuirks": true }"#).unwrap();
+    assert_eq!(options.tickrate, 20);
+    assert_eq!(options.fill_color, "#FFCC00");
+    assert!(options.shift_quirks);
+    assert!(!options.enable_xo);
+    assert!(OctoOptions::from_json("[]").is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_options_round_trip() {
+    let json = r##"{"tickrate":100,"fillColor":"#FFFFFF","fillColor2":"#FF6600","blendColor":"#662200","backgroundColor":"#000000","buzzColor":"#990099","quietColor":"#330033","shiftQuirks":true,"loadStoreQuirks":true,"vfOrderQuirks":false,"clipQuirks":true,"jumpQuirks":false,"vBlankQuirks":false,"logicQuirks":false,"enableXO":true,"screenRotation":180,"maxSize":65024,"touchInputMode":"gamepad","fontStyle":"schip","displayScale":2}"##;
+    let options = OctoOptions::from_json(json).unwrap();
+    assert_eq!(options.screen_rotation, 180);
+    assert!(options.enable_xo);
+    assert_eq!(options.to_json(), json);
+}
+
+#[wasm_bindgen_test]
+fn test_processor_options() {
+    let mut processor = Processor::new();
+    processor.quirks = Quirks::superchip();
+    processor.tick_rate = 30;
+    let options = processor.octo_options();
+    assert!(options.shift_quirks && options.load_store_quirks && options.jump_quirks && options.clip_quirks);
+    assert_eq!(options.tickrate, 30);
+    assert_eq!(options.fill_color, "#FFFFFF");
+
+    let mut other = Processor::new();
+    other.apply_octo_options(&OctoOptions::from_json(&options.to_json()).unwrap()).unwrap();
+    assert_eq!(other.quirks, Quirks::superchip());
+    assert_eq!(other.tick_rate, 30);
+    assert_eq!(other.colors, processor.colors);
+
+    // XO-CHIP und schiefe Drehungen werden abgelehnt, ohne etwas zu übernehmen
+    let mut rejected = Processor::new();
+    assert!(rejected.apply_octo_options(&OctoOptions::from_json(r#"{ "enableXO": true, "tickrate": 99 }"#).unwrap()).is_err());
+    assert!(rejected.apply_octo_options(&OctoOptions::from_json(r#"{ "screenRotation": 45 }"#).unwrap()).is_err());
+    assert_eq!(rejected.tick_rate, tomo::chip8::DEFAULT_TICK_RATE);
+    assert_eq!(rejected.quirks, Quirks::new());
+    let json = r#"{ "program": ": main clear", "options": { "enableXO": true } }"#;
+    assert!(matches!(rejected.load_cartridge(&build_cartridge(json, 1024)), Err(CartridgeError::Options(_))));
+}
@@ -0,0 +1,80 @@
+use wasm_bindgen_test::*;
+
+use log::Level;
+
+use tomo::chip8::processor::{Key, Processor, Register};
+use tomo::platform::TestPlatform;
+use tomo::reporting::target;
+
+fn build_processor(seed: u32) -> (Processor, TestPlatform) {
+    let platform = TestPlatform::new(seed);
+    let processor = Processor::with_platform(Box::new(platform.clone()));
+    (processor, platform)
+}
+
+#[wasm_bindgen_test]
+fn test_random_is_deterministic() {
+    let (mut first, _) = build_processor(1234);
+    let (mut second, _) = build_processor(1234);
+
+    for _ in 0..8 {
+        first.execute(0xC0FF);
+        second.execute(0xC0FF);
+        assert_eq!(first.test_get_registers(0), second.test_get_registers(0));
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_audio_notifications() {
+    let (mut processor, platform) = build_processor(1);
+    processor.test_set_registers(0, 2);
+    // LD ST, V0
+    processor.execute(0xF018);
+    assert_eq!(platform.record().borrow().audio, vec![true]);
+
+    processor.tick();
+    processor.tick();
+    assert_eq!(platform.record().borrow().audio, vec![true, false]);
+}
+
+#[wasm_bindgen_test]
+fn test_diagnostics() {
+    let (mut processor, platform) = build_processor(1);
+    processor.load(&[0x00, 0xE0]).unwrap();
+    processor.execute(0xFFFF);
+
+    // Debug-Ausgaben (z.B. der ROM-Datenbank) werden hier nicht betrachtet
+    let record = platform.record();
+    let logs: Vec<_> = record.borrow().logs.iter().filter(|log| log.level <= Level::Info).cloned().collect();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].level, Level::Info);
+    assert_eq!(logs[0].target, target::LOADER);
+    assert_eq!(logs[1].level, Level::Error);
+    assert_eq!(logs[1].target, target::CPU);
+    assert!(logs[1].message.contains("0xFFFF"));
+    assert_eq!(processor.test_get_registers(Register::ST as usize), 0);
+}
+
+#[wasm_bindgen_test]
+fn test_diagnostic_targets() {
+    let (mut processor, platform) = build_processor(1);
+    processor.key_press(Key::K1);
+    processor.key_up(Key::K1);
+    processor.execute(0x00E0);
+
+    let record = platform.record();
+    let targets: Vec<_> = record.borrow().logs.iter().map(|entry| (entry.level, entry.target.clone())).collect();
+    assert_eq!(targets, vec![
+        (Level::Debug, target::INPUT.to_string()),
+        (Level::Debug, target::INPUT.to_string()),
+        (Level::Trace, target::DISPLAY.to_string()),
+    ]);
+}
+
+#[wasm_bindgen_test]
+fn test_clock() {
+    let (processor, platform) = build_processor(1);
+    assert_eq!(processor.now_micros(), 0);
+    platform.advance(16_667);
+    assert_eq!(processor.now_micros(), 16_667);
+}
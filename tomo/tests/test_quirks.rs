@@ -0,0 +1,115 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::processor::{Processor, Register};
+use tomo::chip8::quirks::Quirks;
+use tomo::chip8::DISPLAY_WIDTH;
+
+fn build_processor(quirks: Quirks) -> Processor {
+    let mut processor = Processor::new();
+    processor.quirks = quirks;
+    processor.pc = 0x200;
+    processor
+}
+
+#[wasm_bindgen_test]
+fn test_shift_quirk() {
+    let mut processor = build_processor(Quirks::cosmac_vip());
+    processor.test_set_registers(0, 0xFF);
+    processor.test_set_registers(1, 0b0000_0011);
+    processor.execute(0x8016);
+    assert_eq!(processor.test_get_registers(0), 0b0000_0001);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 1);
+
+    processor.test_set_registers(1, 0b1000_0001);
+    processor.execute(0x801E);
+    assert_eq!(processor.test_get_registers(0), 0b0000_0010);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 1);
+}
+
+#[wasm_bindgen_test]
+fn test_logic_quirk() {
+    let mut processor = build_processor(Quirks::cosmac_vip());
+    processor.test_set_registers(Register::VF as usize, 1);
+    processor.execute(0x8011);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 0);
+}
+
+#[wasm_bindgen_test]
+fn test_memory_quirks() {
+    let mut processor = build_processor(Quirks::cosmac_vip());
+    processor.i_reg = 0x300;
+    processor.execute(0xF255);
+    assert_eq!(processor.i_reg, 0x303);
+
+    processor.quirks.memory_increment_by_x = true;
+    processor.execute(0xF265);
+    assert_eq!(processor.i_reg, 0x305);
+
+    processor.quirks.memory_leave_i_unchanged = true;
+    processor.execute(0xF265);
+    assert_eq!(processor.i_reg, 0x305);
+}
+
+#[wasm_bindgen_test]
+fn test_jump_quirk() {
+    let mut processor = build_processor(Quirks::superchip());
+    processor.test_set_registers(0, 1);
+    processor.test_set_registers(3, 4);
+    processor.execute(0xB300);
+    assert_eq!(processor.pc, 0x304);
+}
+
+#[wasm_bindgen_test]
+fn test_clip_without_wrap_quirk() {
+    let mut processor = build_processor(Quirks::modern());
+    processor.i_reg = 0x300;
+    processor.test_set_ram(0x300, 0xFF);
+    processor.test_set_registers(0, (DISPLAY_WIDTH - 4) as u8);
+    processor.test_set_registers(1, 0);
+    processor.execute(0xD011);
+    assert!(processor.display.get_pixel(0, DISPLAY_WIDTH - 1));
+    assert!(!processor.display.get_pixel(0, 0));
+}
+
+#[wasm_bindgen_test]
+fn test_vblank_quirk() {
+    let mut processor = build_processor(Quirks::cosmac_vip());
+    processor.i_reg = 0x300;
+    processor.test_set_ram(0x300, 0x80);
+    processor.pc = 0x400;
+    processor.execute(0xD011);
+    assert_eq!(processor.pc, 0x402);
+    // Zweites Zeichnen im selben Frame wartet
+    processor.execute(0xD011);
+    assert_eq!(processor.pc, 0x402);
+    assert!(processor.display.get_pixel(0, 0));
+}
+
+#[wasm_bindgen_test]
+fn test_vf_order_quirk() {
+    let mut processor = build_processor(Quirks::modern());
+    processor.test_set_registers(Register::VF as usize, 0xFF);
+    processor.test_set_registers(1, 0x02);
+    processor.execute(0x8F14);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 1);
+
+    processor.quirks.vf_order = true;
+    processor.test_set_registers(Register::VF as usize, 0xFF);
+    processor.execute(0x8F14);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 0x01);
+    processor.test_set_registers(Register::VF as usize, 0x10);
+    processor.execute(0x8F14);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 0x12);
+}
+
+#[wasm_bindgen_test]
+fn test_vf_order_default() {
+    // Wie bisher bleibt bei SUB, SHR, SUBN und SHL mit X = F das Ergebnis in VF
+    let mut processor = build_processor(Quirks::new());
+    processor.test_set_registers(Register::VF as usize, 0x10);
+    processor.test_set_registers(1, 0x01);
+    processor.execute(0x8F15);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 0x0F);
+    processor.execute(0x8F16);
+    assert_eq!(processor.test_get_registers(Register::VF as usize), 0x07);
+}
@@ -0,0 +1,92 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::recorder::Recorder;
+use tomo::chip8::{Colors, Display};
+
+// Blinkt alle 10 Frames, der Ton läuft in jedem zweiten Abschnitt
+fn build_recorder(scale: u32) -> Recorder {
+    let mut recorder = Recorder::new(Colors::new(0x000000, 0xFFFFFF), scale);
+    let mut display = Display::new();
+    for frame in 0..30 {
+        display.set_pixel(3, 5, frame / 10 % 2 == 1);
+        assert!(recorder.capture_display(&display, frame / 10 == 1));
+    }
+    recorder
+}
+
+// Verzögerungen aller Frames eines GIFs in Hundertstelsekunden
+fn gif_delays(gif: &[u8]) -> Vec<u16> {
+    let mut delays = Vec::new();
+    let mut position = 13 + 6;
+    loop {
+        match gif[position] {
+            0x21 => {
+                if gif[position + 1] == 0xF9 {
+                    delays.push(u16::from_le_bytes([gif[position + 4], gif[position + 5]]));
+                }
+                position += 2;
+                while gif[position] != 0 {
+                    position += gif[position] as usize + 1;
+                }
+                position += 1;
+            }
+            0x2C => {
+                position += 11;
+                while gif[position] != 0 {
+                    position += gif[position] as usize + 1;
+                }
+                position += 1;
+            }
+            0x3B => return delays,
+            block => panic!("unexpected block {:#X}", block),
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_gif() {
+    let recorder = build_recorder(2);
+    assert_eq!(recorder.len(), 30);
+    assert_eq!(recorder.duration(), 500);
+    let gif = recorder.to_gif();
+    assert_eq!(&gif[..6], b"GIF89a");
+    assert_eq!(&gif[6..10], &[128, 0, 64, 0]);
+    // Gleiche Frames werden zusammengefasst: 3 Abschnitte mit je 10 Frames (1/6 s)
+    assert_eq!(gif_delays(&gif), vec![17, 16, 17]);
+}
+
+#[wasm_bindgen_test]
+fn test_y4m() {
+    let recorder = build_recorder(1);
+    let y4m = recorder.to_y4m();
+    let header = b"YUV4MPEG2 W64 H32 F60:1 Ip A1:1 C444\n";
+    assert_eq!(&y4m[..header.len()], header);
+    let frame = 6 + 64 * 32 * 3;
+    assert_eq!(y4m.len(), header.len() + 30 * frame);
+    // Schwarz und Weiß im begrenzten Wertebereich
+    let second = &y4m[header.len() + 10 * frame + 6..];
+    assert_eq!(second[3 * 64 + 5], 235);
+    assert_eq!(second[0], 16);
+    assert_eq!(second[64 * 32], 128);
+}
+
+#[wasm_bindgen_test]
+fn test_wav_and_limit() {
+    let mut recorder = build_recorder(1);
+    let wav = recorder.to_wav(6000, 500);
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    // 30 Frames bei 6000 Hz sind 3000 Samples, davon 1000 mit Ton
+    let samples = &wav[44..];
+    assert_eq!(samples.len(), 3000);
+    assert!(samples[..1000].iter().all(|sample| *sample == 0x80));
+    // 500 Hz: Je 6 Samples hoch und tief, die Phase läuft über die Aufnahme weiter
+    assert!(samples[1002..1008].iter().all(|sample| *sample == 0x40));
+    assert!(samples[1008..1014].iter().all(|sample| *sample == 0xC0));
+    assert!(samples[2000..].iter().all(|sample| *sample == 0x80));
+
+    recorder.limit = 30;
+    assert!(!recorder.capture_display(&Display::new(), false));
+    recorder.clear();
+    assert!(recorder.is_empty());
+}
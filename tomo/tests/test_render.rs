@@ -0,0 +1,89 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::render::{filter_scale, Filter};
+use tomo::chip8::{Colors, Display};
+
+const COLORS: Colors = Colors { background: 0x000000, foreground: 0xFFFFFF, buzzer: 0, silence: 0 };
+
+// Farbe eines Pixels im gerenderten Bild
+fn pixel(image: &[u8], filter: Filter, x: usize, y: usize) -> u32 {
+    let index = (y * 64 * filter_scale(filter) + x) * 4;
+    assert_eq!(image[index + 3], 0xFF);
+    u32::from_be_bytes([0, image[index], image[index + 1], image[index + 2]])
+}
+
+// Eine Treppe von links oben nach rechts unten
+fn build_display() -> Display {
+    let mut display = Display::new();
+    for step in 0..8 {
+        display.set_pixel(10 + step, 10 + step, true);
+        display.set_pixel(10 + step, 11 + step, true);
+    }
+    display
+}
+
+#[wasm_bindgen_test]
+fn test_sizes() {
+    let display = build_display();
+    for (filter, scale) in [
+        (Filter::None, 1),
+        (Filter::Scale2x, 2),
+        (Filter::Epx, 2),
+        (Filter::Scale3x, 3),
+        (Filter::Xbr, 4),
+        (Filter::Crt, 4),
+    ] {
+        assert_eq!(filter_scale(filter), scale);
+        assert_eq!(display.render(&COLORS, filter).len(), 64 * 32 * scale * scale * 4);
+    }
+    let rgba = display.to_rgba(&COLORS);
+    assert_eq!(pixel(&rgba, Filter::None, 10, 10), 0xFFFFFF);
+    assert_eq!(pixel(&rgba, Filter::None, 9, 10), 0x000000);
+}
+
+#[wasm_bindgen_test]
+fn test_scale2x_and_epx() {
+    let display = build_display();
+    let image = display.render(&COLORS, Filter::Scale2x);
+    // Die Stufen der Treppe werden aufgefüllt, einzelne Pixel bleiben Blöcke
+    assert_eq!(pixel(&image, Filter::Scale2x, 2 * 12, 2 * 11 + 1), 0xFFFFFF);
+    assert_eq!(pixel(&image, Filter::Scale2x, 2 * 10 + 1, 2 * 11), 0xFFFFFF);
+    assert_eq!(pixel(&image, Filter::Scale2x, 2 * 10, 2 * 11), 0x000000);
+    // EPX und Scale2x liefern dasselbe Ergebnis
+    assert_eq!(display.render(&COLORS, Filter::Epx), image);
+
+    let mut single = Display::new();
+    single.set_pixel(5, 5, true);
+    let image = single.render(&COLORS, Filter::Scale3x);
+    for (x, y) in [(15, 15), (17, 17), (16, 16)] {
+        assert_eq!(pixel(&image, Filter::Scale3x, x, y), 0xFFFFFF);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_xbr() {
+    let mut single = Display::new();
+    single.set_pixel(5, 5, true);
+    let image = single.render(&COLORS, Filter::Xbr);
+    // Ein einzelnes Pixel verliert seine Ecken, die Kanten werden gemischt
+    assert_eq!(pixel(&image, Filter::Xbr, 20, 20), 0x000000);
+    assert_eq!(pixel(&image, Filter::Xbr, 23, 23), 0x000000);
+    assert_eq!(pixel(&image, Filter::Xbr, 21, 20), 0x7F7F7F);
+    assert_eq!(pixel(&image, Filter::Xbr, 20, 21), 0x7F7F7F);
+    assert_eq!(pixel(&image, Filter::Xbr, 21, 21), 0xFFFFFF);
+    assert_eq!(pixel(&image, Filter::Xbr, 22, 22), 0xFFFFFF);
+}
+
+#[wasm_bindgen_test]
+fn test_crt() {
+    let mut display = Display::new();
+    display.set_pixel(0, 0, true);
+    let image = display.render(&COLORS, Filter::Crt);
+    assert_eq!(pixel(&image, Filter::Crt, 0, 0), 0xFFBFBF);
+    assert_eq!(pixel(&image, Filter::Crt, 1, 0), 0xBFFFBF);
+    assert_eq!(pixel(&image, Filter::Crt, 0, 3), 0x664C4C);
+
+    let mut buffer = vec![0; image.len()];
+    display.render_into(&COLORS, Filter::Crt, &mut buffer);
+    assert_eq!(buffer, image);
+}
@@ -0,0 +1,168 @@
+use wasm_bindgen_test::*;
+
+use tomo::chip8::processor::{Key, Processor, Register, StopReason};
+use tomo::chip8::quirks::Quirks;
+
+// Zählt V0 in einer Endlosschleife hoch
+const COUNTER: [u8; 4] = [
+    0x70, 0x01, // 0x200: ADD V0, 1
+    0x12, 0x00, // 0x202: JP 0x200
+];
+
+fn build_processor(rom: &[u8]) -> Processor {
+    let mut processor = Processor::new();
+    processor.load(rom).unwrap();
+    processor
+}
+
+#[wasm_bindgen_test]
+fn test_run_cycles() {
+    let mut processor = build_processor(&COUNTER);
+    processor.test_set_registers(Register::DT as usize, 5);
+    let result = processor.run(100);
+    assert_eq!(result.cycles, 100);
+    assert_eq!(result.stop, StopReason::Cycles);
+    assert!(!result.display_changed);
+    assert!(!result.sound_active);
+    assert_eq!(processor.test_get_registers(0), 50);
+    // Die Timer laufen nur über `tick_timers`
+    assert_eq!(processor.test_get_registers(Register::DT as usize), 5);
+    processor.tick_timers();
+    assert_eq!(processor.test_get_registers(Register::DT as usize), 4);
+}
+
+#[wasm_bindgen_test]
+fn test_run_until() {
+    let mut processor = build_processor(&COUNTER);
+    let mut loops = 0;
+    let result = processor.run_until(1000, |processor| {
+        loops += (processor.pc == 0x200) as u32;
+        loops == 10
+    });
+    assert_eq!(result.stop, StopReason::Condition);
+    assert_eq!(result.cycles, 20);
+    assert_eq!(processor.pc, 0x200);
+    assert_eq!(processor.test_get_registers(0), 10);
+}
+
+#[wasm_bindgen_test]
+fn test_run_waits_for_key() {
+    let mut processor = build_processor(&[
+        0xF3, 0x0A, // 0x200: LD V3, K
+        0x60, 0x01, // 0x202: LD V0, 1
+        0xF0, 0x18, // 0x204: LD ST, V0
+        0xD0, 0x05, // 0x206: DRW V0, V0, 5
+        0x12, 0x08, // 0x208: JP 0x208
+    ]);
+    let result = processor.run(10);
+    assert_eq!(result.stop, StopReason::WaitingForKey);
+    assert_eq!(result.cycles, 1);
+    assert!(result.waiting_for_key);
+    assert_eq!(processor.pc, 0x200);
+
+    processor.key_press(Key::K5);
+    let result = processor.run(4);
+    assert_eq!(result.stop, StopReason::Cycles);
+    assert!(!result.waiting_for_key);
+    assert!(result.display_changed);
+    assert!(result.sound_active);
+    assert_eq!(processor.test_get_registers(3), Key::K5 as u8);
+}
+
+#[wasm_bindgen_test]
+fn test_run_stops_on_invalid_opcode() {
+    let mut processor = build_processor(&[0x60, 0x01, 0xFF, 0xFF]);
+    let result = processor.run(10);
+    assert_eq!(result.stop, StopReason::InvalidOpcode);
+    assert_eq!(result.cycles, 2);
+    assert_eq!(result.opcode, 0xFFFF);
+}
+
+#[wasm_bindgen_test]
+fn test_run_stops_on_vblank() {
+    let mut processor = build_processor(&[
+        0xD0, 0x01, // 0x200: DRW V0, V0, 1
+        0x12, 0x00, // 0x202: JP 0x200
+    ]);
+    processor.quirks = Quirks::cosmac_vip();
+    let result = processor.run(10);
+    assert_eq!(result.stop, StopReason::VBlank);
+    assert_eq!(result.cycles, 3);
+    assert_eq!(processor.pc, 0x200);
+
+    processor.tick_timers();
+    assert_eq!(processor.run(10).stop, StopReason::VBlank);
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test]
+fn test_run_until_js() {
+    let mut processor = build_processor(&COUNTER);
+    let condition = js_sys::Function::new_with_args("pc", "return pc === 0x202");
+    let result = processor.run_until_js(10, &condition);
+    assert_eq!(result.stop, StopReason::Condition);
+    assert_eq!(result.cycles, 1);
+}
+
+// Wartet, bis der Delay-Timer abgelaufen ist
+const DELAY: [u8; 14] = [
+    0x60, 0x05, // 0x200: LD V0, 5
+    0xF0, 0x15, // 0x202: LD DT, V0
+    0xF1, 0x07, // 0x204: LD V1, DT
+    0x31, 0x00, // 0x206: SE V1, 0
+    0x12, 0x04, // 0x208: JP 0x204
+    0x72, 0x01, // 0x20A: ADD V2, 1
+    0x12, 0x0A, // 0x20C: JP 0x20A
+];
+
+fn assert_same_state(first: &mut Processor, second: &mut Processor) {
+    assert_eq!(first.pc, second.pc);
+    assert_eq!(first.i_reg, second.i_reg);
+    for register in 0..18 {
+        assert_eq!(first.test_get_registers(register), second.test_get_registers(register));
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_fast_forward_delay_loop() {
+    let mut fast = build_processor(&DELAY);
+    let mut slow = build_processor(&DELAY);
+    fast.fast_forward = true;
+    assert!(!slow.fast_forward);
+
+    for cycles in [1000, 7, 333] {
+        let fast_result = fast.run(cycles);
+        let slow_result = slow.run(cycles);
+        assert!(fast_result.idle_cycles > 0);
+        assert_eq!(slow_result.idle_cycles, 0);
+        assert_eq!(fast_result.cycles, slow_result.cycles);
+        assert_eq!(fast_result.opcode, slow_result.opcode);
+        assert_same_state(&mut fast, &mut slow);
+    }
+
+    for _ in 0..5 {
+        fast.tick_timers();
+        slow.tick_timers();
+        assert_eq!(fast.run(100).opcode, slow.run(100).opcode);
+        assert_same_state(&mut fast, &mut slow);
+    }
+    assert_eq!(fast.test_get_registers(2), slow.test_get_registers(2));
+    assert!(fast.test_get_registers(2) > 0);
+}
+
+#[wasm_bindgen_test]
+fn test_no_fast_forward_with_side_effects() {
+    let mut processor = build_processor(&COUNTER);
+    processor.fast_forward = true;
+    assert_eq!(processor.run(1000).idle_cycles, 0);
+
+    // Zeichnen ist eine Nebenwirkung, auch wenn sich der Zustand des CPU wiederholt
+    let mut processor = build_processor(&[
+        0xD0, 0x01, // 0x200: DRW V0, V0, 1
+        0x12, 0x00, // 0x202: JP 0x200
+    ]);
+    processor.fast_forward = true;
+    let result = processor.run(1000);
+    assert_eq!(result.idle_cycles, 0);
+    assert_eq!(result.cycles, 1000);
+}
@@ -0,0 +1,70 @@
+use std::convert::TryInto;
+
+use wasm_bindgen_test::*;
+
+use tomo::chip8::{Colors, Display};
+
+fn build_display() -> Display {
+    let mut display = Display::new();
+    display.set_pixel(0, 0, true);
+    display.set_pixel(0, 63, true);
+    display.set_pixel(31, 1, true);
+    display
+}
+
+// Liest die Chunks eines PNGs (Typ und Daten)
+fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut chunks = Vec::new();
+    let mut position = 8;
+    while position < png.len() {
+        let length = u32::from_be_bytes(png[position..position + 4].try_into().unwrap()) as usize;
+        let kind = String::from_utf8(png[position + 4..position + 8].to_vec()).unwrap();
+        chunks.push((kind, png[position + 8..position + 8 + length].to_vec()));
+        position += 12 + length;
+    }
+    chunks
+}
+
+#[wasm_bindgen_test]
+fn test_png() {
+    let png = build_display().to_png(&Colors::new(0x102030, 0xFFCC00), 3);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let chunks = chunks(&png);
+    let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+    assert_eq!(kinds, vec!["IHDR", "PLTE", "IDAT", "IEND"]);
+    assert_eq!(chunks[0].1, vec![0, 0, 0, 192, 0, 0, 0, 96, 1, 3, 0, 0, 0]);
+    assert_eq!(chunks[1].1, vec![0x10, 0x20, 0x30, 0xFF, 0xCC, 0x00]);
+    // Bekannte Prüfsumme des leeren IEND-Chunks
+    assert_eq!(&png[png.len() - 4..], &[0xAE, 0x42, 0x60, 0x82]);
+    // Wiederholte Zeilen werden komprimiert (unkomprimiert 96 * 25 Bytes)
+    assert!(chunks[2].1.len() < 200);
+}
+
+#[wasm_bindgen_test]
+fn test_pbm_plain() {
+    let pbm = String::from_utf8(build_display().to_pbm_plain(1)).unwrap();
+    let lines: Vec<&str> = pbm.lines().collect();
+    assert_eq!(lines[0], "P1");
+    assert_eq!(lines[1], "64 32");
+    // Jede Zeile des Displays wird auf zwei Zeilen mit je höchstens 70 Zeichen verteilt
+    assert_eq!(lines.len(), 2 + 64);
+    assert!(lines.iter().all(|line| line.len() <= 70));
+    assert!(lines[2].starts_with("1 0 0"));
+    assert!(lines[3].ends_with("0 0 1"));
+    assert!(lines[64].starts_with("0 1 0"));
+}
+
+#[wasm_bindgen_test]
+fn test_pbm_raw() {
+    let pbm = build_display().to_pbm_raw(2);
+    let header = b"P4\n128 64\n";
+    assert_eq!(&pbm[..header.len()], header);
+    let data = &pbm[header.len()..];
+    assert_eq!(data.len(), 16 * 64);
+    assert_eq!(data[0], 0b1100_0000);
+    assert_eq!(data[15], 0b0000_0011);
+    assert_eq!(data[16], 0b1100_0000);
+    assert_eq!(data[62 * 16], 0b0011_0000);
+    // Ein Vergrößerungsfaktor von 0 gilt als 1
+    assert_eq!(Display::new().to_pbm_raw(0).len(), b"P4\n64 32\n".len() + 8 * 32);
+}
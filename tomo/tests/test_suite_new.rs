@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison, clippy::unnecessary_cast)]
+
 use wasm_bindgen_test::*;
 
 use tomo::chip8::{DISPLAY_HEIGHT, DISPLAY_WIDTH, OPCODE_SIZE};
@@ -38,7 +40,7 @@ fn test_initial_state() {
 #[wasm_bindgen_test]
 fn test_load_data() {
     let mut processor = build_processor();
-    processor.load(vec![1, 2, 3]);
+    processor.load(&[1, 2, 3]).unwrap();
     assert_eq!(processor.test_get_ram(0x200), 1);
     assert_eq!(processor.test_get_ram(0x201), 2);
     assert_eq!(processor.test_get_ram(0x202), 3);
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison, clippy::unnecessary_cast)]
+
 use wasm_bindgen_test::*;
 
 use tomo::chip8::DISPLAY_HEIGHT as HOEHE;
@@ -24,7 +26,7 @@ fn test_fetch() {
 #[wasm_bindgen_test]
 fn test_load_data() {
     let mut emu = CHIP8::new();
-    emu.load(vec![1, 2, 3]);
+    emu.load(&[1, 2, 3]).unwrap();
     assert_eq!(emu.test_get_ram(0x200), 1);
     assert_eq!(emu.test_get_ram(0x201), 2);
     assert_eq!(emu.test_get_ram(0x202), 3);
@@ -286,8 +288,9 @@ fn test_execute_0xf000() {
     // Simulation Knopfdruck
     let old_pc = emu.pc;
     emu.execute(0xF00A);
-    // PC sollte um 2 kleiner werden um warten zu simulieren, während auf die Eingabe gewartet wird
-    assert_eq!(old_pc - 2, emu.pc);
+    // PC sollte stehen bleiben, während auf die Eingabe gewartet wird
+    assert_eq!(old_pc, emu.pc);
+    assert!(emu.waiting_for_key());
     emu.current_key = Some(Key::KA);
     emu.execute(0xF00A);
     assert_eq!(emu.test_get_registers(0), 0x0A);